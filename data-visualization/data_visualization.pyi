@@ -0,0 +1,369 @@
+# Generated by `cargo run -p data-visualization --example stubgen`, do not edit.
+
+import os
+from typing import Any
+
+def compute_generations_for_dataset(target_term: str, mutation_range: tuple[int, int], population_range: tuple[int, int], biased_scale: tuple[BiasedScaleType, float], trials: int = 1, seed: int | None = None, max_generations: int | None = None, max_seconds: float | None = None, columnar: bool = False, progress: Any | None = None, cache_dir: str | os.PathLike[str] | None = None, trajectories: bool = False, trajectory_points: int | None = 1000) -> Any:
+    """Simulates every mutation rate and population size in the half-open ranges, see
+    `compute_generations_for_grid`."""
+
+def compute_generations_for_grid(grid: dict[str, Any], trials: int = 1, seed: int | None = None, max_generations: int | None = None, max_seconds: float | None = None, columnar: bool = False, progress: Any | None = None, fitness: Any | None = None, cache_dir: str | os.PathLike[str] | None = None, trajectories: bool = False, trajectory_points: int | None = 1000) -> Any:
+    """Simulates the Cartesian product of `grid`, a dict from parameter names to a value, a list
+    of values or a `Values` range. Grid points without their own `seed` all use `seed`.
+
+    Each trial stops after `max_generations` generations or `max_seconds` of wall-clock time,
+    whichever comes first, and is then reported as censored. With `columnar` the results come
+    back as NumPy columns instead of `SimulationFrame`s, see `frame_columns`.
+
+    `progress(completed_trials, total_trials)` is called periodically while the sweep runs.
+    Interrupting the sweep returns the grid points completed so far.
+
+    `fitness` is an optional Python fitness function shared by every grid point, called as
+    described on `Population`. It runs under the GIL, so it serialises the trials it scores.
+
+    With `cache_dir`, every grid point is stored there under a hash of its parameters, the
+    trial count, the caps and the library version, and later sweeps reuse it instead of
+    simulating it again. Sweeps with a `fitness` function cannot be cached.
+
+    With `trajectories`, every trial also records the best and mean fitness and the diversity
+    of its generations, downsampled to at most `trajectory_points` generations plus the last
+    one, or every generation if `trajectory_points` is None."""
+
+def run_sharded_sweep(grid: dict[str, Any], results_dir: str | os.PathLike[str], shard_size: int = 16, trials: int = 1, seed: int | None = None, max_generations: int | None = None, max_seconds: float | None = None, columnar: bool = False, progress: Any | None = None, trajectories: bool = False, trajectory_points: int | None = 1000) -> Any:
+    """Simulates `grid` like `compute_generations_for_grid`, in shards of `shard_size` grid
+    points whose results are written to `results_dir` as they finish.
+
+    Running the same sweep again with the same `results_dir` resumes it, skipping the
+    finished shards. Several processes on one machine can run the same sweep at once: each
+    claims unfinished shards through lock files in `results_dir`, then waits for the shards
+    the others are simulating, taking over those of processes that died. Every process
+    returns the whole sweep. Without `seed`, processes joining a sweep use the seed it was
+    started with.
+
+    `progress(finished_shards, total_shards)` is called whenever more shards are found
+    finished. Interrupting the sweep raises KeyboardInterrupt, keeping the finished shards."""
+
+def tune(space: dict[str, Any], configurations: int = 32, trials: int = 9, min_trials: int | None = None, eta: int = 3, objective: Objective = Objective.Generations, seed: int | None = None, max_generations: int | None = None, max_seconds: float | None = None, progress: Any | None = None, cache_dir: str | os.PathLike[str] | None = None) -> TuningResult:
+    """Searches `space`, a grid as accepted by `compute_generations_for_grid`, for the
+    configuration minimising `objective`.
+
+    `configurations` points of the grid are drawn at random. Without `min_trials` each is
+    simulated `trials` times. With `min_trials`, successive halving starts every drawn point
+    at `min_trials` trials, then keeps the best `1 / eta` of them and multiplies their trials
+    by `eta` until `trials` is reached. Configurations with fewer censored trials rank first,
+    then those with the lower mean objective. Each round only simulates the trials its
+    configurations do not have yet.
+
+    The returned `TuningResult` holds the best configuration with the 95% confidence interval
+    of its mean objective over the final round's trials. Interrupting the tuner returns the
+    best configuration of the last completed round. An empty `space` raises ValueError."""
+
+def compare_configurations(configurations: list[dict[str, Any]], trials: int = 30, seed: int | None = None, max_generations: int | None = None, max_seconds: float | None = None, progress: Any | None = None, cache_dir: str | os.PathLike[str] | None = None) -> Comparison:
+    """Runs `trials` trials of each of `configurations`, dicts holding a single value per grid
+    parameter as accepted by `compute_generations_for_grid`, and compares their generations to
+    solution with a Kruskal–Wallis test and pairwise Mann–Whitney U tests, Holm-adjusted.
+
+    Configurations without their own `seed` share `seed`, so trial `k` of every configuration
+    starts from the same seed. Censored trials enter the tests as known to take longer than
+    the generation they were stopped at, see `MannWhitney`."""
+
+def render_heatmap(frames: list[SimulationFrame], path: str, statistic: Statistic = Statistic.Mean, size: tuple[int, int] = (800, 600), title: str = "", font: str | os.PathLike[str] | None = None) -> None:
+    """Renders a heatmap of `statistic` of the generations to solution over mutation rate ×
+    population size to `path`, an `.svg` or `.png` file. Trials of frames sharing a cell are
+    pooled, and cells where `statistic` is undefined are grey.
+
+    Text is set in `font`, a TrueType file, defaulting to a common system font and, where
+    there is none, the bundled Fira Sans."""
+
+def render_convergence(frames: list[SimulationFrame], path: str, size: tuple[int, int] = (800, 600), title: str = "", font: str | os.PathLike[str] | None = None) -> None:
+    """Renders the mean best fitness over generations of every frame, with its 95% confidence
+    band, to `path`, an `.svg` or `.png` file. The frames need their trajectories, simulated
+    with `trajectories=True`."""
+
+def render_survival(frames: list[SimulationFrame], path: str, size: tuple[int, int] = (800, 600), title: str = "", font: str | os.PathLike[str] | None = None) -> None:
+    """Renders the Kaplan–Meier survival curve of every frame to `path`, an `.svg` or `.png`
+    file."""
+
+class BiasedScaleType:
+    Multiplicative: BiasedScaleType
+    Order: BiasedScaleType
+    Exponential: BiasedScaleType
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    @staticmethod
+    def from_str(name: str) -> BiasedScaleType:
+        """Variant named `name`, the inverse of `str`."""
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, tuple[str]]: ...
+
+class CrossoverType:
+    SinglePoint: CrossoverType
+    TwoPoint: CrossoverType
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    @staticmethod
+    def from_str(name: str) -> CrossoverType:
+        """Variant named `name`, the inverse of `str`."""
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, tuple[str]]: ...
+
+class Selection:
+    @staticmethod
+    def proportional() -> Selection: ...
+    @staticmethod
+    def tournament(size: int) -> Selection: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, Any]: ...
+    def __repr__(self) -> str: ...
+
+class Values:
+    """Numeric range for a grid parameter. Integer parameters round every value and drop
+    the duplicates that creates."""
+
+    @staticmethod
+    def stepped(start: float, stop: float, step: float = 1.0) -> Values:
+        """`start`, `start + step`, ... up to but excluding `stop`, like `range`."""
+    @staticmethod
+    def log_spaced(start: float, stop: float, num: int) -> Values:
+        """`num` values from `start` to `stop` inclusive, evenly spaced on a log scale."""
+    def values(self) -> list[float]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, Any]: ...
+    def __repr__(self) -> str: ...
+
+class SimulationFrame:
+    """Trials of one grid point. Frames pickle and compare by value, and `to_dict` gives the
+    plain-Python form that `from_dict` reads back."""
+
+    @property
+    def seeds(self) -> list[int]: ...
+    @property
+    def generations_taken(self) -> list[int]: ...
+    @property
+    def censored(self) -> list[bool]:
+        """Whether each trial was stopped by a cap before solving the target."""
+    @property
+    def best_genes(self) -> list[str]: ...
+    @property
+    def elapsed_seconds(self) -> list[float]:
+        """Wall-clock time of each trial, as measured when it was first simulated."""
+    @property
+    def evaluations(self) -> list[int]: ...
+    @property
+    def parameters(self) -> dict[str, Any]:
+        """Grid point of the trials, as accepted by `compute_generations_for_grid`."""
+    @property
+    def trajectories(self) -> list[dict[str, Any]] | None:
+        """Per-trial dicts of `generations`, `best_fitness`, `mean_fitness` and `diversity`
+        columns, or None if the sweep did not record trajectories."""
+    @property
+    def summary(self) -> dict[str, Any]:
+        """Generations to solution over every trial, see `TrialSummary`."""
+    @property
+    def survival(self) -> dict[str, Any]:
+        """Kaplan–Meier estimate over every trial, see `SurvivalCurve`."""
+    def to_dict(self) -> dict[str, Any]:
+        """The parameters and per-trial results. `summary` and `survival` are left out as they
+        follow from the trials."""
+    @staticmethod
+    def from_dict(dict: dict[str, Any]) -> SimulationFrame: ...
+    def __reduce__(self) -> tuple[Any, tuple[dict[str, Any]]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int:
+        """Leaves out the floating-point fields, which equal frames share anyway."""
+    def __repr__(self) -> str: ...
+
+class Objective:
+    """What the tuner minimises."""
+
+    Generations: Objective
+    Evaluations: Objective
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    @staticmethod
+    def from_str(name: str) -> Objective:
+        """Variant named `name`, the inverse of `str`."""
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, tuple[str]]: ...
+
+class TuningResult:
+    @property
+    def parameters(self) -> dict[str, Any]:
+        """Best configuration, as a grid accepted by `compute_generations_for_grid`."""
+    @property
+    def frame(self) -> SimulationFrame:
+        """Trials of the best configuration in the final round."""
+    @property
+    def objective(self) -> Objective: ...
+    @property
+    def mean(self) -> float:
+        """Objective over the final round's trials of the best configuration, see `TrialSummary`."""
+    @property
+    def confidence_interval(self) -> tuple[float, float]: ...
+    @property
+    def num_censored(self) -> int: ...
+    @property
+    def rounds(self) -> list[tuple[int, int]]:
+        """`(configurations, trials)` of every completed round, counting the trials carried over
+        from earlier rounds."""
+    def __repr__(self) -> str: ...
+
+class Comparison:
+    """Results of `compare_configurations`. `groups`, `kruskal_wallis` and `pairwise` are plain
+    dicts and lists of dicts, ready for `pandas.DataFrame`, and notebooks render the whole
+    comparison as tables."""
+
+    @property
+    def frames(self) -> list[SimulationFrame]: ...
+    @property
+    def groups(self) -> list[dict[str, Any]]:
+        """One row per configuration with its parameters and summary."""
+    @property
+    def kruskal_wallis(self) -> dict[str, Any]: ...
+    @property
+    def pairwise(self) -> list[dict[str, Any]]:
+        """One row per pair of configurations `a < b`, see `MannWhitney`."""
+    def _repr_html_(self) -> str: ...
+    def __repr__(self) -> str: ...
+
+class Statistic:
+    """Statistic of generations to solution shown by a heatmap."""
+
+    Mean: Statistic
+    Median: Statistic
+    SurvivalMedian: Statistic
+    def __int__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    @staticmethod
+    def from_str(name: str) -> Statistic:
+        """Variant named `name`, the inverse of `str`."""
+    def __str__(self) -> str: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> tuple[Any, tuple[str]]: ...
+
+class Population:
+    """A `Population` that is evaluated after every generation, so its candidates and stats
+    can be inspected between steps.
+
+    `fitness`, if given, is called with the genes of every generation as a list of strings
+    and returns a list of non-negative floats, used instead of the biased scale to select
+    parents. Exceptions it raises propagate out of the call that evaluated the generation.
+
+    Raises ValueError for an empty population and, without `fitness`, for scales that
+    `Grid` rejects too."""
+
+    def __init__(self, target_term: str, mutation_rate: int, population_size: int, biased_scale: tuple[BiasedScaleType, float], seed: int | None = None, fitness: Any | None = None) -> None: ...
+    @staticmethod
+    def from_snapshot(snapshot: PopulationSnapshot, fitness: Any | None = None) -> Population: ...
+    def snapshot(self) -> PopulationSnapshot: ...
+    def step(self, generations: int = 1) -> GenerationStats:
+        """Advances `generations` generations, stopping early once a candidate matches the target.
+        Signals such as Ctrl-C are handled between generations."""
+    def run(self, max_generations: int | None = None, max_seconds: float | None = None) -> int:
+        """Steps until solved, until `max_generations` is reached or until `max_seconds` have
+        passed, returning the generation. Like `step`, it can be interrupted by signals."""
+    def stats(self) -> GenerationStats: ...
+    def diversity(self) -> float:
+        """Probability that two random candidates differ at a locus, averaged over all loci."""
+    def best(self) -> Dna | None: ...
+    def candidates(self) -> list[Dna]: ...
+    def genes(self) -> list[str]: ...
+    def fitness(self) -> list[int]: ...
+    def track_hall_of_fame(self, capacity: int) -> None: ...
+    def hall_of_fame(self) -> list[tuple[str, int, float, int]]:
+        """Archived `(genes, fitness, score, generation)` tuples, best first. The archive ranks
+        by `score`, which is the fitness unless `fitness` was given."""
+    @property
+    def generation(self) -> int: ...
+    @property
+    def seed(self) -> int: ...
+    @property
+    def target_term(self) -> str: ...
+    @property
+    def population_size(self) -> int: ...
+    @property
+    def mutation_rate(self) -> int: ...
+    def set_mutation_rate(self, mutation_rate: int) -> None: ...
+    def __repr__(self) -> str: ...
+
+class Dna:
+    """A candidate's genes with the fitness it was last evaluated to. Candidates pickle and
+    compare by value, and `to_dict` gives the plain-Python form that `from_dict` reads back."""
+
+    @property
+    def genes(self) -> str: ...
+    @property
+    def fitness(self) -> int: ...
+    @property
+    def biased_fitness(self) -> float: ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @staticmethod
+    def from_dict(dict: dict[str, Any]) -> Dna: ...
+    def __reduce__(self) -> tuple[Any, tuple[dict[str, Any]]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class GenerationStats:
+    """Statistics of one evaluated generation. They pickle and compare by value, and `to_dict`
+    gives the plain-Python form that `from_dict` reads back."""
+
+    @property
+    def generation(self) -> int: ...
+    @property
+    def best_candidate(self) -> int: ...
+    @property
+    def best_fitness(self) -> int: ...
+    @property
+    def mean_fitness(self) -> float: ...
+    @property
+    def worst_fitness(self) -> int: ...
+    @property
+    def solved(self) -> bool: ...
+    def to_dict(self) -> dict[str, Any]: ...
+    @staticmethod
+    def from_dict(dict: dict[str, Any]) -> GenerationStats: ...
+    def __reduce__(self) -> tuple[Any, tuple[dict[str, Any]]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+class PopulationSnapshot:
+    """A copy of a `Population` to resume it from with `Population.from_snapshot`. Snapshots
+    pickle and compare by value, and `to_dict` gives the plain-Python form that `from_dict`
+    reads back. Candidates are compared by their genes and fitness."""
+
+    @property
+    def generation(self) -> int: ...
+    @property
+    def target_term(self) -> str: ...
+    @property
+    def seed(self) -> int: ...
+    @property
+    def genes(self) -> list[str]: ...
+    def to_dict(self) -> dict[str, Any]:
+        """Candidates are laid out as `genes`, `fitness` and `biased_fitness` columns, and the
+        hall of fame, if tracked, as its capacity and `(genes, fitness, score, generation)`
+        entries."""
+    @staticmethod
+    def from_dict(dict: dict[str, Any]) -> PopulationSnapshot: ...
+    def __reduce__(self) -> tuple[Any, tuple[dict[str, Any]]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int:
+        """Leaves out the settings, which rarely differ between snapshots of one run."""
+    def __repr__(self) -> str: ...
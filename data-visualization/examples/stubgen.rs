@@ -0,0 +1,480 @@
+//! Writes `data_visualization.pyi`, the type stubs of the Python module, from the pyo3
+//! definitions in `src`. Run `cargo run -p data-visualization --example stubgen` after
+//! changing anything exported to Python.
+
+use proc_macro2::TokenTree;
+use std::{collections::HashMap, fs, path::Path};
+use syn::{
+    punctuated::Punctuated, Attribute, Expr, FnArg, GenericArgument, ImplItem, ImplItemFn, Item,
+    ItemFn, Lit, Meta, MetaNameValue, Pat, PathArguments, ReturnType, Signature, Stmt, Token, Type,
+};
+
+const HEADER: &str = "\
+# Generated by `cargo run -p data-visualization --example stubgen`, do not edit.
+
+import os
+from typing import Any
+";
+
+struct Class {
+    name: String,
+    doc: Option<String>,
+    /// Variants of a fieldless enum, which pyo3 exposes as class attributes.
+    variants: Option<Vec<String>>,
+    /// `#[pyo3(get)]` fields.
+    fields: Vec<(String, Type, Option<String>)>,
+    methods: Vec<ImplItemFn>,
+    /// Whether `enum_protocols!` adds its methods.
+    protocols: bool,
+}
+
+#[derive(Default)]
+struct Stubs {
+    classes: HashMap<String, Class>,
+    functions: HashMap<String, ItemFn>,
+    aliases: HashMap<String, Type>,
+    /// Rust names of the module's classes and functions, in the order it adds them.
+    classes_added: Vec<String>,
+    functions_added: Vec<String>,
+}
+
+fn string_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Str(value) => Some(value.value()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+/// `key = "value"` arguments of the attribute `name`, such as `#[pyfunction(seed = "None")]`.
+fn attr_values(attrs: &[Attribute], name: &str) -> HashMap<String, String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident(name) && matches!(attr.meta, Meta::List(_)))
+        .flat_map(|attr| {
+            attr.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)
+                .unwrap_or_default()
+        })
+        .filter_map(|pair| {
+            Some((
+                pair.path.get_ident()?.to_string(),
+                string_value(&pair.value)?,
+            ))
+        })
+        .collect()
+}
+
+fn doc(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(pair) if pair.path.is_ident("doc") => string_value(&pair.value),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).to_owned())
+        .collect::<Vec<_>>();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// `def` or `class` line at `indent`, followed by its docstring or `...`.
+fn block(indent: &str, header: &str, doc: Option<&str>) -> String {
+    let Some(doc) = doc else {
+        return format!("{indent}{header} ...\n");
+    };
+    let body = format!("{indent}    ");
+    let doc = doc
+        .lines()
+        .map(|line| match line {
+            "" => String::new(),
+            _ => format!("{body}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{indent}{header}\n{body}\"\"\"{}\"\"\"\n", doc.trim_start())
+}
+
+/// The Python spelling of a pyo3 default value.
+fn py_default(value: &str) -> String {
+    match value {
+        "true" => "True".to_owned(),
+        "false" => "False".to_owned(),
+        _ => value.replace("::", "."),
+    }
+}
+
+impl Stubs {
+    fn collect(&mut self, file: syn::File) {
+        for item in file.items {
+            match item {
+                Item::Struct(item) if has_attr(&item.attrs, "pyclass") => {
+                    let fields = item
+                        .fields
+                        .iter()
+                        .filter(|field| {
+                            field.attrs.iter().any(|attr| {
+                                attr.path().is_ident("pyo3")
+                                    && attr
+                                        .parse_args::<syn::Ident>()
+                                        .is_ok_and(|arg| arg == "get")
+                            })
+                        })
+                        .map(|field| {
+                            (
+                                field.ident.as_ref().unwrap().to_string(),
+                                field.ty.clone(),
+                                doc(&field.attrs),
+                            )
+                        })
+                        .collect();
+                    self.class(&item.ident, &item.attrs).fields = fields;
+                }
+                Item::Enum(item) if has_attr(&item.attrs, "pyclass") => {
+                    let variants = item
+                        .variants
+                        .iter()
+                        .map(|variant| variant.ident.to_string())
+                        .collect();
+                    self.class(&item.ident, &item.attrs).variants = Some(variants);
+                }
+                Item::Impl(item) if has_attr(&item.attrs, "pymethods") => {
+                    let methods = item.items.into_iter().filter_map(|item| match item {
+                        ImplItem::Fn(method) => Some(method),
+                        _ => None,
+                    });
+                    self.class_mut(&type_name(&item.self_ty))
+                        .methods
+                        .extend(methods);
+                }
+                Item::Macro(item) if item.mac.path.is_ident("enum_protocols") => {
+                    let Some(TokenTree::Ident(name)) = item.mac.tokens.into_iter().next() else {
+                        panic!("enum_protocols! must start with the enum's name");
+                    };
+                    self.class_mut(&name.to_string()).protocols = true;
+                }
+                Item::Type(item) => {
+                    self.aliases.insert(item.ident.to_string(), *item.ty);
+                }
+                Item::Fn(item) if has_attr(&item.attrs, "pymodule") => self.collect_added(&item),
+                Item::Fn(item) if has_attr(&item.attrs, "pyfunction") => {
+                    self.functions.insert(item.sig.ident.to_string(), item);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn class(&mut self, ident: &syn::Ident, attrs: &[Attribute]) -> &mut Class {
+        let class = self.class_mut(&ident.to_string());
+        class.name = attr_values(attrs, "pyclass")
+            .remove("name")
+            .unwrap_or_else(|| ident.to_string());
+        class.doc = doc(attrs);
+        class
+    }
+
+    fn class_mut(&mut self, ident: &str) -> &mut Class {
+        self.classes
+            .entry(ident.to_owned())
+            .or_insert_with(|| Class {
+                name: ident.to_owned(),
+                doc: None,
+                variants: None,
+                fields: Vec::new(),
+                methods: Vec::new(),
+                protocols: false,
+            })
+    }
+
+    /// Reads the `m.add_class::<T>()?` and `m.add_function(wrap_pyfunction!(f, m)?)?` calls
+    /// of the `#[pymodule]` function.
+    fn collect_added(&mut self, module: &ItemFn) {
+        for stmt in &module.block.stmts {
+            let Stmt::Expr(Expr::Try(expr), _) = stmt else {
+                continue;
+            };
+            let Expr::MethodCall(call) = &*expr.expr else {
+                continue;
+            };
+            match call.method.to_string().as_str() {
+                "add_class" => {
+                    let turbofish = call.turbofish.as_ref().expect("add_class without a type");
+                    let Some(GenericArgument::Type(ty)) = turbofish.args.first() else {
+                        panic!("add_class without a type");
+                    };
+                    self.classes_added.push(type_name(ty));
+                }
+                "add_function" => {
+                    let Some(Expr::Try(arg)) = call.args.first() else {
+                        panic!("add_function without wrap_pyfunction!");
+                    };
+                    let Expr::Macro(wrap) = &*arg.expr else {
+                        panic!("add_function without wrap_pyfunction!");
+                    };
+                    let args = wrap
+                        .mac
+                        .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)
+                        .expect("wrap_pyfunction! arguments");
+                    let Some(Expr::Path(function)) = args.first() else {
+                        panic!("wrap_pyfunction! without a function");
+                    };
+                    let function = function.path.segments.last().unwrap();
+                    self.functions_added.push(function.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The Python type of `ty`, with `Self` standing for the class `this`.
+    fn py_type(&self, ty: &Type, this: &str) -> String {
+        let path = match ty {
+            Type::Reference(reference) => return self.py_type(&reference.elem, this),
+            Type::Tuple(tuple) if tuple.elems.is_empty() => return "None".to_owned(),
+            Type::Tuple(tuple) => {
+                let elems = tuple
+                    .elems
+                    .iter()
+                    .map(|ty| self.py_type(ty, this))
+                    .collect::<Vec<_>>();
+                return format!("tuple[{}]", elems.join(", "));
+            }
+            Type::Path(path) => path,
+            _ => panic!("no Python type for a {} type", kind(ty)),
+        };
+        let segment = path.path.segments.last().unwrap();
+        let args = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(self.py_type(ty, this)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let name = segment.ident.to_string();
+        match name.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+                "int".to_owned()
+            }
+            "f32" | "f64" => "float".to_owned(),
+            "bool" => "bool".to_owned(),
+            "String" | "str" => "str".to_owned(),
+            "PathBuf" => "str | os.PathLike[str]".to_owned(),
+            "PyAny" | "PyObject" => "Any".to_owned(),
+            "PyDict" => "dict[str, Any]".to_owned(),
+            "Vec" => format!("list[{}]", args[0]),
+            "Option" => format!("{} | None", args[0]),
+            "PyResult" | "Py" | "PyRef" | "PyRefMut" => args[0].clone(),
+            "Self" => this.to_owned(),
+            _ => match (self.classes.get(&name), self.aliases.get(&name)) {
+                (Some(class), _) => class.name.clone(),
+                (None, Some(alias)) => self.py_type(alias, this),
+                (None, None) => panic!("no Python type for {name}"),
+            },
+        }
+    }
+
+    /// The `def` line of `sig`, a method of `this` or, without it, a module function.
+    fn def(
+        &self,
+        name: &str,
+        sig: &Signature,
+        defaults: &HashMap<String, String>,
+        this: &str,
+    ) -> String {
+        // `#[new]` has no receiver but becomes `__init__`, which takes `self`
+        let receiver = (name == "__init__").then(|| "self".to_owned());
+        let params = receiver
+            .into_iter()
+            .chain(sig.inputs.iter().filter_map(|arg| {
+                let FnArg::Typed(arg) = arg else {
+                    return Some("self".to_owned());
+                };
+                let Pat::Ident(pat) = &*arg.pat else {
+                    panic!("{name} has a parameter that is not a plain name");
+                };
+                let param = pat.ident.to_string();
+                match &*arg.ty {
+                    Type::Path(path) if path.path.segments.last().unwrap().ident == "Python" => {
+                        None
+                    }
+                    _ if param == "slf" => Some("self".to_owned()),
+                    ty => Some(match defaults.get(&param) {
+                        Some(default) => format!(
+                            "{param}: {} = {}",
+                            self.py_type(ty, this),
+                            py_default(default)
+                        ),
+                        None => format!("{param}: {}", self.py_type(ty, this)),
+                    }),
+                }
+            }))
+            .collect::<Vec<_>>();
+        let output = match (&sig.output, name) {
+            (_, "__init__") | (ReturnType::Default, _) => "None".to_owned(),
+            (ReturnType::Type(_, ty), _) => self.py_type(ty, this),
+        };
+        format!("def {name}({}) -> {output}:", params.join(", "))
+    }
+
+    fn render_method(&self, method: &ImplItemFn, this: &str) -> String {
+        let attrs = &method.attrs;
+        let doc = doc(attrs);
+        let doc = doc.as_deref();
+        let name = method.sig.ident.to_string();
+        let defaults = attr_values(attrs, "args");
+
+        if name == "__richcmp__" {
+            return ["__eq__", "__ne__"]
+                .map(|name| {
+                    block(
+                        "    ",
+                        &format!("def {name}(self, other: object) -> bool:"),
+                        doc,
+                    )
+                })
+                .concat();
+        }
+        if has_attr(attrs, "new") {
+            return block(
+                "    ",
+                &self.def("__init__", &method.sig, &defaults, this),
+                doc,
+            );
+        }
+        if has_attr(attrs, "getter") {
+            let name = name.strip_prefix("get_").unwrap_or(&name);
+            return format!(
+                "    @property\n{}",
+                block("    ", &self.def(name, &method.sig, &defaults, this), doc)
+            );
+        }
+        let def = block("    ", &self.def(&name, &method.sig, &defaults, this), doc);
+        if has_attr(attrs, "staticmethod") {
+            format!("    @staticmethod\n{def}")
+        } else {
+            def
+        }
+    }
+
+    fn render_class(&self, class: &Class) -> String {
+        let this = &class.name;
+        let mut members = Vec::new();
+        if let Some(variants) = &class.variants {
+            members.extend(
+                variants
+                    .iter()
+                    .map(|variant| format!("    {variant}: {this}\n")),
+            );
+            members.push(
+                "    def __int__(self) -> int: ...\n    \
+                 def __eq__(self, other: object) -> bool: ...\n    \
+                 def __ne__(self, other: object) -> bool: ...\n"
+                    .to_owned(),
+            );
+        }
+        for (name, ty, doc) in &class.fields {
+            let def = format!("def {name}(self) -> {}:", self.py_type(ty, this));
+            members.push(format!(
+                "    @property\n{}",
+                block("    ", &def, doc.as_deref())
+            ));
+        }
+        members.extend(
+            class
+                .methods
+                .iter()
+                .map(|method| self.render_method(method, this)),
+        );
+        if class.protocols {
+            // Mirrors the methods `enum_protocols!` defines
+            members.push(format!(
+                "    @staticmethod\n    def from_str(name: str) -> {this}:\n        \
+                 \"\"\"Variant named `name`, the inverse of `str`.\"\"\"\n    \
+                 def __str__(self) -> str: ...\n    \
+                 def __hash__(self) -> int: ...\n    \
+                 def __reduce__(self) -> tuple[Any, tuple[str]]: ...\n"
+            ));
+        }
+
+        let header = format!("class {this}:");
+        match (members.is_empty(), &class.doc) {
+            (true, doc) => block("", &header, doc.as_deref()),
+            (false, None) => format!("{header}\n{}", members.concat()),
+            (false, Some(doc)) => {
+                format!("{}\n{}", block("", &header, Some(doc)), members.concat())
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let functions = self.functions_added.iter().map(|name| {
+            let function = &self.functions[name];
+            let defaults = attr_values(&function.attrs, "pyfunction");
+            block(
+                "",
+                &self.def(name, &function.sig, &defaults, ""),
+                doc(&function.attrs).as_deref(),
+            )
+        });
+        let classes = self
+            .classes_added
+            .iter()
+            .map(|name| self.render_class(&self.classes[name]));
+        [HEADER.to_owned()]
+            .into_iter()
+            .chain(functions)
+            .chain(classes)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn kind(ty: &Type) -> &'static str {
+    match ty {
+        Type::Array(_) => "array",
+        Type::Slice(_) => "slice",
+        Type::Ptr(_) => "pointer",
+        Type::ImplTrait(_) | Type::TraitObject(_) => "trait",
+        _ => "non-path",
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
+        _ => panic!("expected a named type, found a {} type", kind(ty)),
+    }
+}
+
+fn main() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut sources = fs::read_dir(crate_dir.join("src"))
+        .expect("failed to list src")
+        .map(|entry| entry.expect("failed to list src").path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "rs"))
+        .collect::<Vec<_>>();
+    sources.sort();
+
+    let mut stubs = Stubs::default();
+    for path in sources {
+        let source = fs::read_to_string(&path).expect("failed to read source");
+        stubs.collect(
+            syn::parse_file(&source)
+                .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display())),
+        );
+    }
+
+    let path = crate_dir.join("data_visualization.pyi");
+    fs::write(&path, stubs.render()).expect("failed to write stubs");
+    println!("wrote {}", path.display());
+}
@@ -3,8 +3,16 @@ from time import time
 from data_visualization import data_visualization as dv
 
 start = time()
-for elm in dv.compute_generations_for_dataset(
-    "Hello World", (1, 5), (100, 150), (dv.BiasedScaleType.Order, 2.43)
-):
+frames = dv.compute_generations_for_dataset(
+    "Hello World",
+    (1, 5),
+    (100, 150),
+    (dv.BiasedScaleType.Order, 2.43),
+    trials=10,
+    max_generations=10_000,
+)
+for elm in frames:
     print(elm)
 print(time() - start)
+
+dv.render_heatmap(frames, "generations.svg", title="Hello World")
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{grid::SimulationParameters, SweepOptions, TrialResults};
+
+/// Bumped whenever the meaning of a cached entry changes without the library version changing.
+const CACHE_FORMAT: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    trials: TrialResults,
+}
+
+/// Directory of finished grid points, one JSON file per point named after a hash of
+/// everything its results depend on. The full key is stored alongside the results so a
+/// hash collision reads as a miss.
+pub struct SweepCache {
+    directory: PathBuf,
+}
+
+impl SweepCache {
+    pub fn open(directory: &Path) -> io::Result<Self> {
+        fs::create_dir_all(directory)?;
+        Ok(Self {
+            directory: directory.to_owned(),
+        })
+    }
+
+    pub fn key(parameters: &SimulationParameters, options: &SweepOptions) -> String {
+        format!(
+            "format={CACHE_FORMAT};version={};target_term={:?};mutation_rate={};population_size={};\
+             biased_scale={:?};selection={:?};crossover={:?};elitism={};seed={};trials={}..{};\
+             max_generations={:?};max_duration={:?};trajectories={};trajectory_points={:?}",
+            env!("CARGO_PKG_VERSION"),
+            parameters.target_term,
+            parameters.mutation_rate,
+            parameters.population_size,
+            parameters.biased_scale,
+            parameters.selection,
+            parameters.crossover,
+            parameters.elitism,
+            parameters.seed,
+            options.first_trial,
+            options.trials,
+            options.limits.max_generations,
+            options.limits.max_duration,
+            options.trajectories,
+            options.trajectory_points,
+        )
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory
+            .join(format!("{:016x}.json", fnv1a(key.as_bytes())))
+    }
+
+    /// Unreadable or mismatching entries count as misses.
+    pub fn load(&self, key: &str) -> Option<TrialResults> {
+        let entry = serde_json::from_slice::<CacheEntry>(&fs::read(self.path(key)).ok()?).ok()?;
+        (entry.key == key).then_some(entry.trials)
+    }
+
+    pub fn store(&self, key: &str, trials: &TrialResults) -> io::Result<()> {
+        let entry = CacheEntry {
+            key: key.to_owned(),
+            trials: trials.clone(),
+        };
+        write_atomically(&self.path(key), &serde_json::to_vec(&entry)?)
+    }
+}
+
+/// Writes through a temporary file, so concurrent processes never read a partial file.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    fs::write(&temporary, contents)?;
+    fs::rename(temporary, path)
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
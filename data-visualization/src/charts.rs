@@ -0,0 +1,148 @@
+use monkey_charts::{Chart, ChartError, TrialSet};
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+use shakespeare_monkey_solver::trajectory::TrajectoryPoint;
+use std::path::{Path, PathBuf};
+
+use crate::{protocols::enum_protocols, SimulationFrame};
+
+/// Statistic of generations to solution shown by a heatmap.
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Statistic {
+    Mean,
+    Median,
+    /// Kaplan–Meier median, which accounts for censored trials but is undefined when fewer
+    /// than half the trials were solved.
+    SurvivalMedian,
+}
+
+enum_protocols!(Statistic {
+    Mean,
+    Median,
+    SurvivalMedian
+});
+
+impl From<Statistic> for monkey_charts::Statistic {
+    fn from(statistic: Statistic) -> Self {
+        match statistic {
+            Statistic::Mean => Self::Mean,
+            Statistic::Median => Self::Median,
+            Statistic::SurvivalMedian => Self::SurvivalMedian,
+        }
+    }
+}
+
+impl From<&SimulationFrame> for TrialSet {
+    fn from(frame: &SimulationFrame) -> Self {
+        let trajectories = frame.trajectories.iter().flatten().map(|trajectory| {
+            (0..trajectory.generations.len())
+                .map(|idx| TrajectoryPoint {
+                    generation: trajectory.generations[idx],
+                    best_fitness: trajectory.best_fitness[idx],
+                    mean_fitness: trajectory.mean_fitness[idx],
+                    diversity: trajectory.diversity[idx],
+                })
+                .collect()
+        });
+        Self {
+            samples: frame
+                .generations_taken
+                .iter()
+                .copied()
+                .zip(frame.censored.iter().copied())
+                .collect(),
+            trajectories: trajectories.collect(),
+            ..TrialSet::new(
+                frame.parameters.mutation_rate,
+                frame.parameters.population_size,
+                frame.parameters.target_term.chars().count(),
+            )
+        }
+    }
+}
+
+fn render(
+    chart: Chart,
+    path: &str,
+    size: (u32, u32),
+    title: &str,
+    font: Option<PathBuf>,
+) -> PyResult<()> {
+    chart
+        .render(Path::new(path), size, title, font.as_deref())
+        .map_err(|err| match err {
+            ChartError::Draw(_) | ChartError::Font(..) => PyIOError::new_err(err.to_string()),
+            _ => PyValueError::new_err(err.to_string()),
+        })
+}
+
+/// Renders a heatmap of `statistic` of the generations to solution over mutation rate ×
+/// population size to `path`, an `.svg` or `.png` file. Trials of frames sharing a cell are
+/// pooled, and cells where `statistic` is undefined are grey.
+///
+/// Text is set in `font`, a TrueType file, defaulting to a common system font and, where
+/// there is none, the bundled Fira Sans.
+#[pyfunction(
+    statistic = "Statistic::Mean",
+    size = "(800, 600)",
+    title = "\"\"",
+    font = "None"
+)]
+pub fn render_heatmap(
+    frames: Vec<PyRef<SimulationFrame>>,
+    path: &str,
+    statistic: Statistic,
+    size: (u32, u32),
+    title: &str,
+    font: Option<PathBuf>,
+) -> PyResult<()> {
+    let sets = frames
+        .iter()
+        .map(|frame| (&**frame).into())
+        .collect::<Vec<_>>();
+    render(
+        Chart::Heatmap(&sets, statistic.into()),
+        path,
+        size,
+        title,
+        font,
+    )
+}
+
+/// Renders the mean best fitness over generations of every frame, with its 95% confidence
+/// band, to `path`, an `.svg` or `.png` file. The frames need their trajectories, simulated
+/// with `trajectories=True`.
+#[pyfunction(size = "(800, 600)", title = "\"\"", font = "None")]
+pub fn render_convergence(
+    frames: Vec<PyRef<SimulationFrame>>,
+    path: &str,
+    size: (u32, u32),
+    title: &str,
+    font: Option<PathBuf>,
+) -> PyResult<()> {
+    let sets = frames
+        .iter()
+        .map(|frame| (&**frame).into())
+        .collect::<Vec<_>>();
+    render(Chart::Convergence(&sets), path, size, title, font)
+}
+
+/// Renders the Kaplan–Meier survival curve of every frame to `path`, an `.svg` or `.png`
+/// file.
+#[pyfunction(size = "(800, 600)", title = "\"\"", font = "None")]
+pub fn render_survival(
+    frames: Vec<PyRef<SimulationFrame>>,
+    path: &str,
+    size: (u32, u32),
+    title: &str,
+    font: Option<PathBuf>,
+) -> PyResult<()> {
+    let sets = frames
+        .iter()
+        .map(|frame| (&**frame).into())
+        .collect::<Vec<_>>();
+    render(Chart::Survival(&sets), path, size, title, font)
+}
@@ -0,0 +1,292 @@
+use pyo3::{
+    prelude::*,
+    types::{PyByteArray, PyDict},
+};
+use shakespeare_monkey_solver::population::Selection;
+
+use crate::SimulationFrame;
+
+enum Column {
+    U64(Vec<u64>),
+    F64(Vec<f64>),
+    Bool(Vec<bool>),
+    Str(Vec<String>),
+}
+
+impl Column {
+    /// Builds a NumPy array straight from the column's bytes, or a stdlib `array.array` when
+    /// NumPy is not installed. String columns stay lists.
+    fn into_array(self, py: Python, numpy: Option<&PyModule>) -> PyResult<PyObject> {
+        let (bytes, dtype, typecode): (Vec<u8>, _, _) = match self {
+            Column::U64(values) => (
+                values
+                    .iter()
+                    .flat_map(|value| value.to_ne_bytes())
+                    .collect(),
+                "u8",
+                "Q",
+            ),
+            Column::F64(values) => (
+                values
+                    .iter()
+                    .flat_map(|value| value.to_ne_bytes())
+                    .collect(),
+                "f8",
+                "d",
+            ),
+            Column::Bool(values) => (values.iter().map(|&value| value as u8).collect(), "?", "B"),
+            Column::Str(values) => return Ok(values.into_py(py)),
+        };
+        let buffer = PyByteArray::new(py, &bytes);
+
+        Ok(match numpy {
+            Some(numpy) => numpy.call_method1("frombuffer", (buffer, dtype))?.into(),
+            None => PyModule::import(py, "array")?
+                .call_method1("array", (typecode, buffer))?
+                .into(),
+        })
+    }
+}
+
+fn into_dict(py: Python, columns: Vec<(&str, Column)>) -> PyResult<Py<PyDict>> {
+    let numpy = PyModule::import(py, "numpy").ok();
+    let dict = PyDict::new(py);
+    for (name, column) in columns {
+        dict.set_item(name, column.into_array(py, numpy)?)?;
+    }
+    Ok(dict.into())
+}
+
+/// Lays `frames` out as `{"points": ..., "trials": ...}`, two dicts of equally long named
+/// columns. `points` has a row per grid point with its parameters and summary, `trials`
+/// a row per trial, linked to its grid point by the `point` column. With recorded
+/// trajectories a third dict, `generations`, has a row per recorded generation, linked to
+/// its trial by the `point` and `trial` columns.
+pub fn frame_columns(py: Python, frames: &[SimulationFrame]) -> PyResult<Py<PyDict>> {
+    let u64_column =
+        |value: fn(&SimulationFrame) -> u64| Column::U64(frames.iter().map(value).collect());
+    let f64_column =
+        |value: fn(&SimulationFrame) -> f64| Column::F64(frames.iter().map(value).collect());
+    let str_column =
+        |value: fn(&SimulationFrame) -> String| Column::Str(frames.iter().map(value).collect());
+
+    let points = into_dict(
+        py,
+        vec![
+            (
+                "target_term",
+                str_column(|frame| frame.parameters.target_term.clone()),
+            ),
+            (
+                "mutation_rate",
+                u64_column(|frame| frame.parameters.mutation_rate as u64),
+            ),
+            (
+                "population_size",
+                u64_column(|frame| frame.parameters.population_size as u64),
+            ),
+            (
+                "scale_type",
+                str_column(|frame| format!("{:?}", frame.parameters.biased_scale.0)),
+            ),
+            (
+                "scale_factor",
+                f64_column(|frame| frame.parameters.biased_scale.1),
+            ),
+            (
+                "selection",
+                str_column(|frame| match frame.parameters.selection {
+                    Selection::Proportional => "Proportional".to_owned(),
+                    Selection::Tournament(_) => "Tournament".to_owned(),
+                }),
+            ),
+            (
+                "tournament_size",
+                u64_column(|frame| match frame.parameters.selection {
+                    Selection::Proportional => 0,
+                    Selection::Tournament(size) => size as u64,
+                }),
+            ),
+            (
+                "crossover",
+                str_column(|frame| format!("{:?}", frame.parameters.crossover)),
+            ),
+            (
+                "elitism",
+                u64_column(|frame| frame.parameters.elitism as u64),
+            ),
+            ("seed", u64_column(|frame| frame.parameters.seed)),
+            (
+                "num_trials",
+                u64_column(|frame| frame.generations_taken.len() as u64),
+            ),
+            (
+                "num_censored",
+                u64_column(|frame| {
+                    frame.censored.iter().filter(|&&censored| censored).count() as u64
+                }),
+            ),
+            ("mean", f64_column(|frame| frame.summary.mean)),
+            ("median", f64_column(|frame| frame.summary.median)),
+            ("std_dev", f64_column(|frame| frame.summary.std_dev)),
+            ("min", u64_column(|frame| frame.summary.min as u64)),
+            ("max", u64_column(|frame| frame.summary.max as u64)),
+            (
+                "ci_low",
+                f64_column(|frame| frame.summary.confidence_interval.0),
+            ),
+            (
+                "ci_high",
+                f64_column(|frame| frame.summary.confidence_interval.1),
+            ),
+            (
+                "survival_median",
+                f64_column(|frame| {
+                    frame
+                        .survival
+                        .median
+                        .map_or(f64::NAN, |median| median as f64)
+                }),
+            ),
+        ],
+    )?;
+
+    let trial_rows = || {
+        frames.iter().enumerate().flat_map(|(point, frame)| {
+            (0..frame.seeds.len()).map(move |trial| (point, frame, trial))
+        })
+    };
+    let trials = into_dict(
+        py,
+        vec![
+            (
+                "point",
+                Column::U64(trial_rows().map(|(point, _, _)| point as u64).collect()),
+            ),
+            (
+                "trial",
+                Column::U64(trial_rows().map(|(_, _, trial)| trial as u64).collect()),
+            ),
+            (
+                "seed",
+                Column::U64(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.seeds[trial])
+                        .collect(),
+                ),
+            ),
+            (
+                "generations",
+                Column::U64(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.generations_taken[trial] as u64)
+                        .collect(),
+                ),
+            ),
+            (
+                "censored",
+                Column::Bool(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.censored[trial])
+                        .collect(),
+                ),
+            ),
+            (
+                "best_genes",
+                Column::Str(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.best_genes[trial].clone())
+                        .collect(),
+                ),
+            ),
+            (
+                "elapsed_seconds",
+                Column::F64(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.elapsed_seconds[trial])
+                        .collect(),
+                ),
+            ),
+            (
+                "evaluations",
+                Column::U64(
+                    trial_rows()
+                        .map(|(_, frame, trial)| frame.evaluations[trial] as u64)
+                        .collect(),
+                ),
+            ),
+        ],
+    )?;
+
+    let dict = PyDict::new(py);
+    dict.set_item("points", points)?;
+    dict.set_item("trials", trials)?;
+    if frames.iter().any(|frame| frame.trajectories.is_some()) {
+        dict.set_item("generations", generation_columns(py, frames)?)?;
+    }
+    Ok(dict.into())
+}
+
+fn generation_columns(py: Python, frames: &[SimulationFrame]) -> PyResult<Py<PyDict>> {
+    let generation_rows =
+        || {
+            frames.iter().enumerate().flat_map(|(point, frame)| {
+                frame.trajectories.iter().flatten().enumerate().flat_map(
+                    move |(trial, trajectory)| {
+                        (0..trajectory.generations.len())
+                            .map(move |idx| (point, trial, trajectory, idx))
+                    },
+                )
+            })
+        };
+
+    into_dict(
+        py,
+        vec![
+            (
+                "point",
+                Column::U64(generation_rows().map(|(point, ..)| point as u64).collect()),
+            ),
+            (
+                "trial",
+                Column::U64(
+                    generation_rows()
+                        .map(|(_, trial, ..)| trial as u64)
+                        .collect(),
+                ),
+            ),
+            (
+                "generation",
+                Column::U64(
+                    generation_rows()
+                        .map(|(_, _, trajectory, idx)| trajectory.generations[idx] as u64)
+                        .collect(),
+                ),
+            ),
+            (
+                "best_fitness",
+                Column::U64(
+                    generation_rows()
+                        .map(|(_, _, trajectory, idx)| trajectory.best_fitness[idx] as u64)
+                        .collect(),
+                ),
+            ),
+            (
+                "mean_fitness",
+                Column::F64(
+                    generation_rows()
+                        .map(|(_, _, trajectory, idx)| trajectory.mean_fitness[idx])
+                        .collect(),
+                ),
+            ),
+            (
+                "diversity",
+                Column::F64(
+                    generation_rows()
+                        .map(|(_, _, trajectory, idx)| trajectory.diversity[idx])
+                        .collect(),
+                ),
+            ),
+        ],
+    )
+}
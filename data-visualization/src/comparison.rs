@@ -0,0 +1,214 @@
+use pyo3::{
+    exceptions::{PyKeyboardInterrupt, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use shakespeare_monkey_solver::{
+    statistics::{holm, KruskalWallis, MannWhitney},
+    utils::random::Random,
+};
+use std::path::PathBuf;
+
+use crate::{grid::expand_grid, simulate_grid, SimulationFrame, SweepOptions};
+
+/// Results of `compare_configurations`. `groups`, `kruskal_wallis` and `pairwise` are plain
+/// dicts and lists of dicts, ready for `pandas.DataFrame`, and notebooks render the whole
+/// comparison as tables.
+#[pyclass(module = "data_visualization.data_visualization")]
+pub struct Comparison {
+    #[pyo3(get)]
+    frames: Vec<Py<SimulationFrame>>,
+    kruskal_wallis: KruskalWallis,
+    /// `(a, b, test, Holm-adjusted p-value)` for every pair of configurations.
+    pairwise: Vec<(usize, usize, MannWhitney, f64)>,
+}
+
+fn samples(frame: &SimulationFrame) -> Vec<(usize, bool)> {
+    frame
+        .generations_taken
+        .iter()
+        .copied()
+        .zip(frame.censored.iter().copied())
+        .collect()
+}
+
+#[pymethods]
+impl Comparison {
+    /// One row per configuration with its parameters and summary.
+    #[getter]
+    fn groups(&self, py: Python) -> PyResult<Vec<Py<PyDict>>> {
+        self.frames
+            .iter()
+            .enumerate()
+            .map(|(idx, frame)| {
+                let frame = frame.borrow(py);
+                let row = frame.parameters.to_dict(py)?;
+                let row = row.as_ref(py);
+                row.set_item("configuration", idx)?;
+                row.set_item("trials", frame.generations_taken.len())?;
+                row.set_item(
+                    "num_censored",
+                    frame.censored.iter().filter(|&&censored| censored).count(),
+                )?;
+                row.set_item("mean", frame.summary.mean)?;
+                row.set_item("median", frame.summary.median)?;
+                row.set_item("survival_median", frame.survival.median)?;
+                Ok(row.into())
+            })
+            .collect()
+    }
+
+    #[getter]
+    fn kruskal_wallis(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("statistic", self.kruskal_wallis.statistic)?;
+        dict.set_item("degrees_of_freedom", self.kruskal_wallis.degrees_of_freedom)?;
+        dict.set_item("p_value", self.kruskal_wallis.p_value)?;
+        Ok(dict.into())
+    }
+
+    /// One row per pair of configurations `a < b`, see `MannWhitney`.
+    #[getter]
+    fn pairwise(&self, py: Python) -> PyResult<Vec<Py<PyDict>>> {
+        self.pairwise
+            .iter()
+            .map(|&(a, b, test, p_adjusted)| {
+                let row = PyDict::new(py);
+                row.set_item("a", a)?;
+                row.set_item("b", b)?;
+                row.set_item("u", test.u)?;
+                row.set_item("cliffs_delta", test.cliffs_delta)?;
+                row.set_item("z", test.z)?;
+                row.set_item("p_value", test.p_value)?;
+                row.set_item("p_adjusted", p_adjusted)?;
+                Ok(row.into())
+            })
+            .collect()
+    }
+
+    fn _repr_html_(&self, py: Python) -> String {
+        let mut html = String::from(
+            "<table><tr><th>configuration</th><th>trials</th><th>censored</th><th>mean</th>\
+             <th>median</th><th>survival median</th></tr>",
+        );
+        for (idx, frame) in self.frames.iter().enumerate() {
+            let frame = frame.borrow(py);
+            html += &format!(
+                "<tr><td>{idx}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>",
+                frame.generations_taken.len(),
+                frame.censored.iter().filter(|&&censored| censored).count(),
+                frame.summary.mean,
+                frame.summary.median,
+                frame
+                    .survival
+                    .median
+                    .map_or("-".to_owned(), |median| median.to_string()),
+            );
+        }
+        html += &format!(
+            "</table><p>Kruskal–Wallis H = {:.3}, df = {}, p = {:.3e}</p>\
+             <table><tr><th>a</th><th>b</th><th>U</th><th>Cliff's δ</th><th>z</th><th>p</th>\
+             <th>p (Holm)</th></tr>",
+            self.kruskal_wallis.statistic,
+            self.kruskal_wallis.degrees_of_freedom,
+            self.kruskal_wallis.p_value,
+        );
+        for &(a, b, test, p_adjusted) in &self.pairwise {
+            html += &format!(
+                "<tr><td>{a}</td><td>{b}</td><td>{}</td><td>{:.3}</td><td>{:.3}</td>\
+                 <td>{:.3e}</td><td>{:.3e}</td></tr>",
+                test.u, test.cliffs_delta, test.z, test.p_value, p_adjusted,
+            );
+        }
+        html + "</table>"
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Comparison(configurations={}, kruskal_wallis={:?}, pairwise={:?})",
+            self.frames.len(),
+            self.kruskal_wallis,
+            self.pairwise
+        )
+    }
+}
+
+/// Runs `trials` trials of each of `configurations`, dicts holding a single value per grid
+/// parameter as accepted by `compute_generations_for_grid`, and compares their generations to
+/// solution with a Kruskal–Wallis test and pairwise Mann–Whitney U tests, Holm-adjusted.
+///
+/// Configurations without their own `seed` share `seed`, so trial `k` of every configuration
+/// starts from the same seed. Censored trials enter the tests as known to take longer than
+/// the generation they were stopped at, see `MannWhitney`.
+#[pyfunction(
+    trials = "30",
+    seed = "None",
+    max_generations = "None",
+    max_seconds = "None",
+    progress = "None",
+    cache_dir = "None"
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn compare_configurations(
+    py: Python,
+    configurations: Vec<&PyDict>,
+    trials: usize,
+    seed: Option<u64>,
+    max_generations: Option<usize>,
+    max_seconds: Option<f64>,
+    progress: Option<&PyAny>,
+    cache_dir: Option<PathBuf>,
+) -> PyResult<Comparison> {
+    if configurations.len() < 2 {
+        return Err(PyValueError::new_err(
+            "at least two configurations are needed",
+        ));
+    }
+    let options = SweepOptions::new(trials, max_generations, max_seconds, progress, cache_dir)?;
+    let seed = seed.unwrap_or_else(Random::entropy_seed);
+
+    let mut parameter_sets = Vec::with_capacity(configurations.len());
+    for (idx, configuration) in configurations.into_iter().enumerate() {
+        let mut points = expand_grid(configuration, seed)?;
+        if points.len() != 1 {
+            return Err(PyValueError::new_err(format!(
+                "configuration {idx} has {} grid points, expected one",
+                points.len()
+            )));
+        }
+        parameter_sets.append(&mut points);
+    }
+
+    let num_configurations = parameter_sets.len();
+    let frames = simulate_grid(py, parameter_sets, &options)?;
+    if frames.len() < num_configurations {
+        return Err(PyKeyboardInterrupt::new_err(
+            "comparison was interrupted before every configuration was simulated",
+        ));
+    }
+
+    let groups = frames.iter().map(samples).collect::<Vec<_>>();
+    let tests = (0..groups.len())
+        .flat_map(|a| (a + 1..groups.len()).map(move |b| (a, b)))
+        .map(|(a, b)| (a, b, MannWhitney::test(&groups[a], &groups[b])))
+        .collect::<Vec<_>>();
+    let p_adjusted = holm(
+        &tests
+            .iter()
+            .map(|(_, _, test)| test.p_value)
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(Comparison {
+        kruskal_wallis: KruskalWallis::test(&groups.iter().map(Vec::as_slice).collect::<Vec<_>>()),
+        pairwise: tests
+            .into_iter()
+            .zip(p_adjusted)
+            .map(|((a, b, test), p_adjusted)| (a, b, test, p_adjusted))
+            .collect(),
+        frames: frames
+            .into_iter()
+            .map(|frame| Py::new(py, frame))
+            .collect::<PyResult<_>>()?,
+    })
+}
@@ -0,0 +1,71 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+use shakespeare_monkey_solver::{dna::Dna, fitness::FitnessFunction};
+use std::sync::{Arc, Mutex};
+
+/// First error raised by a Python fitness function, shared with whoever drives the run.
+pub type FitnessError = Arc<Mutex<Option<PyErr>>>;
+
+/// Calls a Python callable with the genes of a whole generation as a list of strings and
+/// uses the list of floats it returns as the candidates' scores.
+///
+/// The engine cannot fail mid-generation, so after an error every candidate scores 0 and
+/// the error is kept in `error` for the caller to raise.
+pub struct PyFitnessFunction {
+    callable: PyObject,
+    error: FitnessError,
+}
+
+impl PyFitnessFunction {
+    pub fn new(callable: PyObject, error: FitnessError) -> Self {
+        Self { callable, error }
+    }
+
+    fn call(&self, population: &[Dna]) -> PyResult<Vec<f64>> {
+        let genes = population.iter().map(Dna::to_string).collect::<Vec<_>>();
+        let scores =
+            Python::with_gil(|py| self.callable.call1(py, (genes,))?.extract::<Vec<f64>>(py))?;
+
+        if scores.len() != population.len() {
+            return Err(PyValueError::new_err(format!(
+                "fitness function returned {} scores for {} candidates",
+                scores.len(),
+                population.len()
+            )));
+        }
+        if !scores
+            .iter()
+            .all(|score| score.is_finite() && *score >= 0.0)
+        {
+            return Err(PyValueError::new_err(
+                "fitness scores must be finite and non-negative",
+            ));
+        }
+        Ok(scores)
+    }
+}
+
+impl FitnessFunction for PyFitnessFunction {
+    fn score(&mut self, population: &[Dna], scores: &mut Vec<f64>) {
+        if !has_fitness_error(&self.error) {
+            match self.call(population) {
+                Ok(new_scores) => return scores.extend(new_scores),
+                Err(err) => {
+                    self.error.lock().unwrap().get_or_insert(err);
+                }
+            }
+        }
+        scores.resize(population.len(), 0.0);
+    }
+}
+
+pub fn has_fitness_error(error: &FitnessError) -> bool {
+    error.lock().unwrap().is_some()
+}
+
+/// Raises the error a fitness function left in `error`, if any.
+pub fn take_fitness_error(error: &FitnessError) -> PyResult<()> {
+    match error.lock().unwrap().take() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,465 @@
+use pyo3::{
+    basic::CompareOp,
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyString, PyTuple},
+};
+use shakespeare_monkey_solver::{
+    dna::Crossover,
+    population::{Population, Selection},
+};
+
+use crate::{
+    protocols::{enum_protocols, hash_of, required, richcmp},
+    BiasedScaleStore, BiasedScaleType,
+};
+
+const PARAMETERS: [&str; 9] = [
+    "target_term",
+    "mutation_rate",
+    "population_size",
+    "scale_type",
+    "scale_factor",
+    "selection",
+    "crossover",
+    "elitism",
+    "seed",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum ValueSpec {
+    Stepped { start: f64, stop: f64, step: f64 },
+    LogSpaced { start: f64, stop: f64, num: usize },
+}
+
+impl ValueSpec {
+    fn expand(&self) -> Vec<f64> {
+        match *self {
+            ValueSpec::Stepped { start, stop, step } => {
+                // Tolerates rounding in `step` so that `stop` stays exclusive
+                let count = ((stop - start) / step - 1e-9).ceil().max(0.0) as usize;
+                (0..count).map(|idx| start + idx as f64 * step).collect()
+            }
+            ValueSpec::LogSpaced { start, stop, num } => (0..num)
+                .map(|idx| match num {
+                    1 => start,
+                    _ => start * (stop / start).powf(idx as f64 / (num - 1) as f64),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Numeric range for a grid parameter. Integer parameters round every value and drop
+/// the duplicates that creates.
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Values(ValueSpec);
+
+#[pymethods]
+impl Values {
+    /// `start`, `start + step`, ... up to but excluding `stop`, like `range`.
+    #[staticmethod]
+    #[args(step = "1.0")]
+    fn stepped(start: f64, stop: f64, step: f64) -> PyResult<Self> {
+        if step == 0.0 || !step.is_finite() {
+            return Err(PyValueError::new_err("step must be finite and non-zero"));
+        }
+        Ok(Self(ValueSpec::Stepped { start, stop, step }))
+    }
+
+    /// `num` values from `start` to `stop` inclusive, evenly spaced on a log scale.
+    #[staticmethod]
+    fn log_spaced(start: f64, stop: f64, num: usize) -> PyResult<Self> {
+        if start <= 0.0 || stop <= 0.0 {
+            return Err(PyValueError::new_err("start and stop must be positive"));
+        }
+        Ok(Self(ValueSpec::LogSpaced { start, stop, num }))
+    }
+
+    fn values(&self) -> Vec<f64> {
+        self.0.expand()
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        // Adding zero folds -0.0 into 0.0, which compares equal to it
+        let bits = |value: f64| (value + 0.0).to_bits();
+        match self.0 {
+            ValueSpec::Stepped { start, stop, step } => {
+                hash_of(&(0, bits(start), bits(stop), bits(step)))
+            }
+            ValueSpec::LogSpaced { start, stop, num } => {
+                hash_of(&(1, bits(start), bits(stop), num as u64))
+            }
+        }
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, PyObject)> {
+        let py = slf.py();
+        let values = py.get_type::<Self>();
+        Ok(match slf.0 {
+            ValueSpec::Stepped { start, stop, step } => (
+                values.getattr("stepped")?.into(),
+                (start, stop, step).into_py(py),
+            ),
+            ValueSpec::LogSpaced { start, stop, num } => (
+                values.getattr("log_spaced")?.into(),
+                (start, stop, num).into_py(py),
+            ),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        match self.0 {
+            ValueSpec::Stepped { start, stop, step } => {
+                format!("Values.stepped({start:?}, {stop:?}, {step:?})")
+            }
+            ValueSpec::LogSpaced { start, stop, num } => {
+                format!("Values.log_spaced({start:?}, {stop:?}, {num})")
+            }
+        }
+    }
+}
+
+#[pyclass(name = "Selection", module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PySelection(pub Selection);
+
+#[pymethods]
+impl PySelection {
+    #[staticmethod]
+    fn proportional() -> Self {
+        Self(Selection::Proportional)
+    }
+
+    #[staticmethod]
+    fn tournament(size: usize) -> PyResult<Self> {
+        if size == 0 {
+            return Err(PyValueError::new_err("tournament size must be at least 1"));
+        }
+        Ok(Self(Selection::Tournament(size)))
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_of(&self.0)
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, PyObject)> {
+        let py = slf.py();
+        let selection = py.get_type::<Self>();
+        Ok(match slf.0 {
+            Selection::Proportional => (
+                selection.getattr("proportional")?.into(),
+                PyTuple::empty(py).into(),
+            ),
+            Selection::Tournament(size) => {
+                (selection.getattr("tournament")?.into(), (size,).into_py(py))
+            }
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        match self.0 {
+            Selection::Proportional => "Selection.proportional()".to_owned(),
+            Selection::Tournament(size) => format!("Selection.tournament({size})"),
+        }
+    }
+}
+
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum CrossoverType {
+    SinglePoint,
+    TwoPoint,
+}
+
+enum_protocols!(CrossoverType {
+    SinglePoint,
+    TwoPoint
+});
+
+impl From<Crossover> for CrossoverType {
+    fn from(crossover: Crossover) -> Self {
+        match crossover {
+            Crossover::SinglePoint => CrossoverType::SinglePoint,
+            Crossover::TwoPoint => CrossoverType::TwoPoint,
+        }
+    }
+}
+
+impl From<CrossoverType> for Crossover {
+    fn from(crossover: CrossoverType) -> Self {
+        match crossover {
+            CrossoverType::SinglePoint => Crossover::SinglePoint,
+            CrossoverType::TwoPoint => Crossover::TwoPoint,
+        }
+    }
+}
+
+/// Every setting of one simulated grid point. Trials derive their seeds from `seed`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationParameters {
+    pub target_term: String,
+    pub mutation_rate: usize,
+    pub population_size: usize,
+    pub biased_scale: BiasedScaleStore,
+    pub selection: Selection,
+    pub crossover: Crossover,
+    pub elitism: usize,
+    pub seed: u64,
+}
+
+impl SimulationParameters {
+    pub fn build_population(&self, seed: u64) -> Population {
+        let mut population = Population::with_seed(
+            self.target_term.clone(),
+            self.mutation_rate,
+            self.population_size,
+            self.biased_scale.0.build_with_factor(self.biased_scale.1),
+            seed,
+        );
+        population.selection = self.selection;
+        population.crossover = self.crossover;
+        population.elitism = self.elitism;
+        population
+    }
+
+    /// The parameters as a dict that `compute_generations_for_grid` accepts as a grid.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("target_term", &self.target_term)?;
+        dict.set_item("mutation_rate", self.mutation_rate)?;
+        dict.set_item("population_size", self.population_size)?;
+        dict.set_item("scale_type", self.biased_scale.0.clone().into_py(py))?;
+        dict.set_item("scale_factor", self.biased_scale.1)?;
+        dict.set_item("selection", PySelection(self.selection).into_py(py))?;
+        dict.set_item("crossover", CrossoverType::from(self.crossover).into_py(py))?;
+        dict.set_item("elitism", self.elitism)?;
+        dict.set_item("seed", self.seed)?;
+        Ok(dict.into())
+    }
+
+    /// Inverse of `to_dict`: a grid holding a single point, including its `seed`.
+    pub fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let grid = Grid::parse(dict, required(dict, "seed")?)?;
+        match grid.num_points() {
+            1 => Ok(grid.point(0)),
+            num_points => Err(PyValueError::new_err(format!(
+                "parameters have {num_points} grid points, expected one"
+            ))),
+        }
+    }
+}
+
+trait GridValue: Sized + PartialEq + for<'a> FromPyObject<'a> {
+    fn from_number(_value: f64) -> Option<Self> {
+        None
+    }
+}
+
+impl GridValue for usize {
+    fn from_number(value: f64) -> Option<Self> {
+        (value.is_finite() && value >= 0.0).then(|| value.round() as usize)
+    }
+}
+
+impl GridValue for u64 {
+    fn from_number(value: f64) -> Option<Self> {
+        (value.is_finite() && value >= 0.0).then(|| value.round() as u64)
+    }
+}
+
+impl GridValue for f64 {
+    fn from_number(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl GridValue for String {}
+impl GridValue for BiasedScaleType {}
+impl GridValue for PySelection {}
+impl GridValue for CrossoverType {}
+
+/// Accepts a `Values` range, any other iterable of values, or a single value.
+fn grid_values<T: GridValue>(grid: &PyDict, name: &str, default: Option<T>) -> PyResult<Vec<T>> {
+    let value = match (grid.get_item(name), default) {
+        (Some(value), _) => value,
+        (None, Some(default)) => return Ok(vec![default]),
+        (None, None) => {
+            return Err(PyValueError::new_err(format!(
+                "missing grid parameter {name}"
+            )))
+        }
+    };
+    let invalid = |err: PyErr| PyValueError::new_err(format!("invalid value for {name}: {err}"));
+
+    if let Ok(values) = value.extract::<Values>() {
+        let mut values = values
+            .0
+            .expand()
+            .into_iter()
+            .map(|number| {
+                T::from_number(number).ok_or_else(|| {
+                    PyValueError::new_err(format!("{name} cannot take the value {number}"))
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        values.dedup();
+        return Ok(values);
+    }
+    if value.is_instance_of::<PyString>()? || value.iter().is_err() {
+        return Ok(vec![value.extract().map_err(invalid)?]);
+    }
+    value
+        .iter()?
+        .map(|item| item?.extract().map_err(invalid))
+        .collect()
+}
+
+/// Rejects scales that `BiasedScale::validate` rejects.
+pub fn validate_scale(
+    biased_scale: &BiasedScaleStore,
+    num_genes: usize,
+    population_size: usize,
+) -> PyResult<()> {
+    let (scale_type, scale_factor) = biased_scale;
+    scale_type
+        .build_with_factor(*scale_factor)
+        .validate(num_genes, population_size)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+pub fn validate_population_size(population_size: usize) -> PyResult<()> {
+    if population_size == 0 {
+        return Err(PyValueError::new_err("population_size must be at least 1"));
+    }
+    Ok(())
+}
+
+/// Value lists of every parameter of a grid, whose points are their Cartesian product.
+pub struct Grid {
+    target_terms: Vec<String>,
+    scale_types: Vec<BiasedScaleType>,
+    scale_factors: Vec<f64>,
+    selections: Vec<PySelection>,
+    crossovers: Vec<CrossoverType>,
+    elitisms: Vec<usize>,
+    seeds: Vec<u64>,
+    mutation_rates: Vec<usize>,
+    population_sizes: Vec<usize>,
+}
+
+impl Grid {
+    /// Reads `grid`, which maps parameter names to their values. `target_term`,
+    /// `mutation_rate`, `population_size`, `scale_type` and `scale_factor` are required, the
+    /// rest default to the solver's defaults and `default_seed`. Fails on empty populations
+    /// and on scales that `validate_scale` rejects.
+    pub fn parse(grid: &PyDict, default_seed: u64) -> PyResult<Self> {
+        for key in grid.keys() {
+            let key = key.extract::<String>()?;
+            if !PARAMETERS.contains(&key.as_str()) {
+                return Err(PyValueError::new_err(format!(
+                    "unknown grid parameter {key}, expected one of {}",
+                    PARAMETERS.join(", ")
+                )));
+            }
+        }
+
+        let grid = Self {
+            target_terms: grid_values(grid, "target_term", None)?,
+            scale_types: grid_values(grid, "scale_type", None)?,
+            scale_factors: grid_values(grid, "scale_factor", None)?,
+            selections: grid_values(grid, "selection", Some(PySelection(Selection::default())))?,
+            crossovers: grid_values(grid, "crossover", Some(CrossoverType::SinglePoint))?,
+            elitisms: grid_values(grid, "elitism", Some(0))?,
+            seeds: grid_values(grid, "seed", Some(default_seed))?,
+            mutation_rates: grid_values(grid, "mutation_rate", None)?,
+            population_sizes: grid_values(grid, "population_size", None)?,
+        };
+        grid.validate()?;
+        Ok(grid)
+    }
+
+    fn validate(&self) -> PyResult<()> {
+        for &population_size in &self.population_sizes {
+            validate_population_size(population_size)?;
+        }
+        // Tournament selection never weighs candidates by their biased fitness
+        if !self
+            .selections
+            .iter()
+            .any(|selection| selection.0 == Selection::Proportional)
+        {
+            return Ok(());
+        }
+        let max_population_size = self.population_sizes.iter().copied().max().unwrap_or(0);
+        for target_term in &self.target_terms {
+            for scale_type in &self.scale_types {
+                for &scale_factor in &self.scale_factors {
+                    validate_scale(
+                        &(scale_type.clone(), scale_factor),
+                        target_term.chars().count(),
+                        max_population_size,
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.target_terms.len()
+            * self.scale_types.len()
+            * self.scale_factors.len()
+            * self.selections.len()
+            * self.crossovers.len()
+            * self.elitisms.len()
+            * self.seeds.len()
+            * self.mutation_rates.len()
+            * self.population_sizes.len()
+    }
+
+    /// Point `idx` of the product, with `population_size` varying fastest.
+    pub fn point(&self, mut idx: usize) -> SimulationParameters {
+        // Decodes the product index as mixed-radix digits, the last parameter fastest
+        let mut pick = |len: usize| {
+            let digit = idx % len;
+            idx /= len;
+            digit
+        };
+        let population_size = self.population_sizes[pick(self.population_sizes.len())];
+        let mutation_rate = self.mutation_rates[pick(self.mutation_rates.len())];
+        let seed = self.seeds[pick(self.seeds.len())];
+        let elitism = self.elitisms[pick(self.elitisms.len())];
+        let crossover = self.crossovers[pick(self.crossovers.len())].clone();
+        let selection = self.selections[pick(self.selections.len())].0;
+        let scale_factor = self.scale_factors[pick(self.scale_factors.len())];
+        let scale_type = self.scale_types[pick(self.scale_types.len())].clone();
+        let target_term = self.target_terms[pick(self.target_terms.len())].clone();
+
+        SimulationParameters {
+            target_term,
+            mutation_rate,
+            population_size,
+            biased_scale: (scale_type, scale_factor),
+            selection,
+            crossover: crossover.into(),
+            elitism,
+            seed,
+        }
+    }
+}
+
+/// Expands `grid` into every point of its Cartesian product, see `Grid::parse`.
+pub fn expand_grid(grid: &PyDict, default_seed: u64) -> PyResult<Vec<SimulationParameters>> {
+    let grid = Grid::parse(grid, default_seed)?;
+    Ok((0..grid.num_points()).map(|idx| grid.point(idx)).collect())
+}
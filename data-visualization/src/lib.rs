@@ -1,37 +1,60 @@
-use pyo3::prelude::*;
+// pyo3 0.16 expands `#[new]` into an `impl` nested inside a static
+#![allow(non_local_definitions)]
+
+mod cache;
+mod charts;
+mod columns;
+mod comparison;
+mod fitness;
+mod grid;
+mod population;
+mod protocols;
+mod shards;
+mod tuner;
+
+use cache::SweepCache;
+use charts::Statistic;
+use comparison::Comparison;
+use fitness::{has_fitness_error, take_fitness_error, FitnessError, PyFitnessFunction};
+use grid::{CrossoverType, PySelection, SimulationParameters, Values};
+use population::{PyDna, PyGenerationStats, PyPopulation, PyPopulationSnapshot};
+use protocols::{enum_protocols, hash_of, required, richcmp};
+use pyo3::{
+    basic::CompareOp, exceptions::PyValueError, panic::PanicException, prelude::*, types::PyDict,
+};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use shakespeare_monkey_solver::{biased_scale::BiasedScale, population::Population};
+use serde::{Deserialize, Serialize};
+use shakespeare_monkey_solver::{
+    biased_scale::BiasedScale,
+    run::{RunLimits, RunOutcome, RunResult},
+    statistics::{SurvivalCurve, TrialSummary},
+    trajectory::Trajectory,
+    utils::{derive_seed, random::Random},
+};
+use std::{
+    any::Any,
+    ops::ControlFlow,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::Duration,
+};
+use tuner::{Objective, TuningResult};
 
-fn total_generations_taken_to_simulate(
-    target_term: &str,
-    mutation_rate: usize,
-    population_size: usize,
-    biased_scale: BiasedScale,
-) -> usize {
-    let mut population = Population::new(
-        target_term.to_owned(),
-        mutation_rate,
-        population_size,
-        biased_scale,
-    );
-    let mut generation_counter = 0;
-
-    loop {
-        population.compute_biased_fitness();
-        if population
-            .population
-            .par_iter()
-            .any(|candidate| candidate.fitness == target_term.len())
-        {
-            return generation_counter;
-        }
-        population.update_generation();
-        generation_counter += 1;
-    }
+/// How often a running sweep checks for signals and reports progress.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+fn run_limits(max_generations: Option<usize>, max_seconds: Option<f64>) -> PyResult<RunLimits> {
+    Ok(RunLimits {
+        max_generations,
+        max_duration: max_seconds
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|_| PyValueError::new_err("max_seconds must be a non-negative number"))?,
+    })
 }
 
-#[pyclass]
-#[derive(Clone, Debug)]
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
 pub enum BiasedScaleType {
     Multiplicative,
     Order,
@@ -39,6 +62,14 @@ pub enum BiasedScaleType {
 }
 
 impl BiasedScaleType {
+    fn of(biased_scale: BiasedScale) -> BiasedScaleStore {
+        match biased_scale {
+            BiasedScale::Multiplicative(factor) => (BiasedScaleType::Multiplicative, factor),
+            BiasedScale::Order(factor) => (BiasedScaleType::Order, factor),
+            BiasedScale::Exponential(factor) => (BiasedScaleType::Exponential, factor),
+        }
+    }
+
     fn build_with_factor(&self, factor: f64) -> BiasedScale {
         match self {
             BiasedScaleType::Multiplicative => BiasedScale::Multiplicative(factor),
@@ -48,58 +79,714 @@ impl BiasedScaleType {
     }
 }
 
+enum_protocols!(BiasedScaleType {
+    Multiplicative,
+    Order,
+    Exponential
+});
+
 pub type BiasedScaleStore = (BiasedScaleType, f64);
 
-#[pyclass]
-#[derive(Debug)]
+/// Recorded generations of one trial as parallel columns, see `Trajectory`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrialTrajectory {
+    pub generations: Vec<usize>,
+    pub best_fitness: Vec<usize>,
+    pub mean_fitness: Vec<f64>,
+    pub diversity: Vec<f64>,
+}
+
+impl TrialTrajectory {
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("generations", &self.generations)?;
+        dict.set_item("best_fitness", &self.best_fitness)?;
+        dict.set_item("mean_fitness", &self.mean_fitness)?;
+        dict.set_item("diversity", &self.diversity)?;
+        Ok(dict.into())
+    }
+
+    fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let trajectory = Self {
+            generations: required(dict, "generations")?,
+            best_fitness: required(dict, "best_fitness")?,
+            mean_fitness: required(dict, "mean_fitness")?,
+            diversity: required(dict, "diversity")?,
+        };
+        let len = trajectory.generations.len();
+        if [
+            trajectory.best_fitness.len(),
+            trajectory.mean_fitness.len(),
+            trajectory.diversity.len(),
+        ]
+        .iter()
+        .any(|&other| other != len)
+        {
+            return Err(PyValueError::new_err(
+                "trajectory columns must all have the same length",
+            ));
+        }
+        Ok(trajectory)
+    }
+}
+
+impl From<Trajectory> for TrialTrajectory {
+    fn from(trajectory: Trajectory) -> Self {
+        let points = trajectory.into_points();
+        Self {
+            generations: points.iter().map(|point| point.generation).collect(),
+            best_fitness: points.iter().map(|point| point.best_fitness).collect(),
+            mean_fitness: points.iter().map(|point| point.mean_fitness).collect(),
+            diversity: points.iter().map(|point| point.diversity).collect(),
+        }
+    }
+}
+
+/// Per-trial outcomes of one grid point, in trial order.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TrialResults {
+    pub seeds: Vec<u64>,
+    pub generations_taken: Vec<usize>,
+    pub censored: Vec<bool>,
+    /// Fittest genes of the final generation.
+    pub best_genes: Vec<String>,
+    pub elapsed_seconds: Vec<f64>,
+    pub evaluations: Vec<usize>,
+    pub trajectories: Option<Vec<TrialTrajectory>>,
+}
+
+impl From<SimulationFrame> for TrialResults {
+    fn from(frame: SimulationFrame) -> Self {
+        Self {
+            seeds: frame.seeds,
+            generations_taken: frame.generations_taken,
+            censored: frame.censored,
+            best_genes: frame.best_genes,
+            elapsed_seconds: frame.elapsed_seconds,
+            evaluations: frame.evaluations,
+            trajectories: frame.trajectories,
+        }
+    }
+}
+
+impl TrialResults {
+    /// Appends `later`, the trials run after these.
+    fn append(&mut self, later: TrialResults) {
+        self.seeds.extend(later.seeds);
+        self.generations_taken.extend(later.generations_taken);
+        self.censored.extend(later.censored);
+        self.best_genes.extend(later.best_genes);
+        self.elapsed_seconds.extend(later.elapsed_seconds);
+        self.evaluations.extend(later.evaluations);
+        if let (Some(trajectories), Some(later)) = (&mut self.trajectories, later.trajectories) {
+            trajectories.extend(later);
+        }
+    }
+}
+
+/// Trials of one grid point. Frames pickle and compare by value, and `to_dict` gives the
+/// plain-Python form that `from_dict` reads back.
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Debug, PartialEq)]
 pub struct SimulationFrame {
-    pub mutation_rate: usize,
-    pub population_size: usize,
-    pub biased_scale: BiasedScaleStore,
-    pub generations_taken: usize,
+    pub parameters: SimulationParameters,
+    #[pyo3(get)]
+    pub seeds: Vec<u64>,
+    #[pyo3(get)]
+    pub generations_taken: Vec<usize>,
+    /// Whether each trial was stopped by a cap before solving the target.
+    #[pyo3(get)]
+    pub censored: Vec<bool>,
+    #[pyo3(get)]
+    pub best_genes: Vec<String>,
+    /// Wall-clock time of each trial, as measured when it was first simulated.
+    #[pyo3(get)]
+    pub elapsed_seconds: Vec<f64>,
+    #[pyo3(get)]
+    pub evaluations: Vec<usize>,
+    /// Only recorded when the sweep asked for trajectories.
+    pub trajectories: Option<Vec<TrialTrajectory>>,
+    pub summary: TrialSummary,
+    pub survival: SurvivalCurve,
+}
+
+impl SimulationFrame {
+    fn new(parameters: SimulationParameters, trials: TrialResults) -> Self {
+        let samples = trials
+            .generations_taken
+            .iter()
+            .copied()
+            .zip(trials.censored.iter().copied())
+            .collect::<Vec<_>>();
+
+        Self {
+            parameters,
+            summary: TrialSummary::from_samples(&trials.generations_taken),
+            survival: SurvivalCurve::kaplan_meier(&samples),
+            seeds: trials.seeds,
+            generations_taken: trials.generations_taken,
+            censored: trials.censored,
+            best_genes: trials.best_genes,
+            elapsed_seconds: trials.elapsed_seconds,
+            evaluations: trials.evaluations,
+            trajectories: trials.trajectories,
+        }
+    }
+
+    /// The frame holding these trials followed by those of `later`, a frame of the same grid
+    /// point simulated from the next trial on.
+    fn extended(self, later: SimulationFrame) -> Self {
+        let parameters = self.parameters.clone();
+        let mut trials = TrialResults::from(self);
+        trials.append(later.into());
+        Self::new(parameters, trials)
+    }
 }
 
 #[pymethods]
 impl SimulationFrame {
-    fn __repr__(&self) -> String {
-        format!("{:?}", self)
+    /// Grid point of the trials, as accepted by `compute_generations_for_grid`.
+    #[getter]
+    fn parameters(&self, py: Python) -> PyResult<Py<PyDict>> {
+        self.parameters.to_dict(py)
+    }
+
+    /// Per-trial dicts of `generations`, `best_fitness`, `mean_fitness` and `diversity`
+    /// columns, or None if the sweep did not record trajectories.
+    #[getter]
+    fn trajectories(&self, py: Python) -> PyResult<Option<Vec<Py<PyDict>>>> {
+        self.trajectories
+            .as_ref()
+            .map(|trajectories| {
+                trajectories
+                    .iter()
+                    .map(|trajectory| trajectory.to_dict(py))
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Generations to solution over every trial, see `TrialSummary`.
+    #[getter]
+    fn summary(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("mean", self.summary.mean)?;
+        dict.set_item("median", self.summary.median)?;
+        dict.set_item("std_dev", self.summary.std_dev)?;
+        dict.set_item("min", self.summary.min)?;
+        dict.set_item("max", self.summary.max)?;
+        dict.set_item("confidence_interval", self.summary.confidence_interval)?;
+        Ok(dict.into())
+    }
+
+    /// Kaplan–Meier estimate over every trial, see `SurvivalCurve`.
+    #[getter]
+    fn survival(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("generations", &self.survival.generations)?;
+        dict.set_item("survival", &self.survival.survival)?;
+        dict.set_item("median", self.survival.median)?;
+        Ok(dict.into())
+    }
+
+    /// The parameters and per-trial results. `summary` and `survival` are left out as they
+    /// follow from the trials.
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("parameters", self.parameters.to_dict(py)?)?;
+        dict.set_item("seeds", &self.seeds)?;
+        dict.set_item("generations_taken", &self.generations_taken)?;
+        dict.set_item("censored", &self.censored)?;
+        dict.set_item("best_genes", &self.best_genes)?;
+        dict.set_item("elapsed_seconds", &self.elapsed_seconds)?;
+        dict.set_item("evaluations", &self.evaluations)?;
+        dict.set_item("trajectories", self.trajectories(py)?)?;
+        Ok(dict.into())
+    }
+
+    #[staticmethod]
+    fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let trajectories = required::<Option<Vec<&PyDict>>>(dict, "trajectories")?
+            .map(|trajectories| {
+                trajectories
+                    .into_iter()
+                    .map(TrialTrajectory::from_dict)
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .transpose()?;
+        let trials = TrialResults {
+            seeds: required(dict, "seeds")?,
+            generations_taken: required(dict, "generations_taken")?,
+            censored: required(dict, "censored")?,
+            best_genes: required(dict, "best_genes")?,
+            elapsed_seconds: required(dict, "elapsed_seconds")?,
+            evaluations: required(dict, "evaluations")?,
+            trajectories,
+        };
+
+        let num_trials = trials.seeds.len();
+        if [
+            trials.generations_taken.len(),
+            trials.censored.len(),
+            trials.best_genes.len(),
+            trials.elapsed_seconds.len(),
+            trials.evaluations.len(),
+            trials.trajectories.as_ref().map_or(num_trials, Vec::len),
+        ]
+        .iter()
+        .any(|&len| len != num_trials)
+        {
+            return Err(PyValueError::new_err(
+                "every per-trial list must have one entry per seed",
+            ));
+        }
+        Ok(Self::new(
+            SimulationParameters::from_dict(required(dict, "parameters")?)?,
+            trials,
+        ))
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, (Py<PyDict>,))> {
+        let py = slf.py();
+        Ok((
+            py.get_type::<Self>().getattr("from_dict")?.into(),
+            (slf.to_dict(py)?,),
+        ))
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    /// Leaves out the floating-point fields, which equal frames share anyway.
+    fn __hash__(&self) -> isize {
+        let parameters = &self.parameters;
+        hash_of(&(
+            &parameters.target_term,
+            parameters.mutation_rate,
+            parameters.population_size,
+            parameters.seed,
+            &self.seeds,
+            &self.generations_taken,
+            &self.censored,
+            &self.best_genes,
+            &self.evaluations,
+        ))
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "SimulationFrame(parameters={}, generations_taken={:?}, num_censored={})",
+            self.parameters(py)?.as_ref(py),
+            self.generations_taken,
+            self.censored.iter().filter(|&&censored| censored).count()
+        ))
+    }
+}
+
+/// Settings shared by every grid point of a sweep.
+struct SweepOptions<'a> {
+    /// Trials `first_trial..trials` are simulated, so later sweeps can add trials to a grid
+    /// point without repeating the earlier ones. Must be below `trials`.
+    first_trial: usize,
+    trials: usize,
+    limits: RunLimits,
+    progress: Option<&'a PyAny>,
+    fitness: Option<&'a PyObject>,
+    cache: Option<SweepCache>,
+    /// Whether to record every trial's `Trajectory`, and with at most how many points.
+    trajectories: bool,
+    trajectory_points: Option<usize>,
+}
+
+impl<'a> SweepOptions<'a> {
+    fn new(
+        trials: usize,
+        max_generations: Option<usize>,
+        max_seconds: Option<f64>,
+        progress: Option<&'a PyAny>,
+        cache_dir: Option<PathBuf>,
+    ) -> PyResult<Self> {
+        if trials == 0 {
+            return Err(PyValueError::new_err("trials must be at least 1"));
+        }
+        Ok(Self {
+            first_trial: 0,
+            trials,
+            limits: run_limits(max_generations, max_seconds)?,
+            progress,
+            fitness: None,
+            cache: cache_dir.as_deref().map(SweepCache::open).transpose()?,
+            trajectories: false,
+            trajectory_points: None,
+        })
+    }
+}
+
+/// Runs its closure when dropped, also while unwinding from a panic.
+struct OnDrop<F: FnMut()>(F);
+
+impl<F: FnMut()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        (self.0)()
+    }
+}
+
+/// The payload of a panicked sweep as a `PanicException`, formatted like the panic message.
+fn panic_error(payload: Box<dyn Any + Send>) -> PyErr {
+    let message = if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else {
+        "panic from Rust code".to_owned()
+    };
+    PanicException::new_err(message)
+}
+
+/// Runs trials `first_trial..trials`, independently seeded, for every parameter set in
+/// parallel. Trial `k` uses the same seed, derived from the set's `seed`, at every grid point, so
+/// growing the grid leaves existing results unchanged.
+///
+/// The GIL is released while the trials run. `progress` is called with the number of
+/// completed and total trials. On KeyboardInterrupt the running trials are abandoned and
+/// only the grid points whose trials all completed are returned. The first exception raised
+/// by `fitness`, see `PyPopulation`, stops the sweep and is re-raised, and a panicking trial
+/// stops it with a `PanicException`.
+///
+/// Grid points found in `cache` are not simulated again, and newly simulated ones are
+/// added to it unless a trial was cut short by the time cap, which is not reproducible.
+fn simulate_grid(
+    py: Python,
+    parameter_sets: Vec<SimulationParameters>,
+    options: &SweepOptions,
+) -> PyResult<Vec<SimulationFrame>> {
+    let SweepOptions {
+        first_trial,
+        trials,
+        ref limits,
+        progress,
+        fitness,
+        ref cache,
+        trajectories,
+        trajectory_points,
+    } = *options;
+    if first_trial >= trials {
+        return Err(PyValueError::new_err(format!(
+            "first trial {first_trial} leaves no trials to run out of {trials}"
+        )));
+    }
+    if cache.is_some() && fitness.is_some() {
+        return Err(PyValueError::new_err(
+            "sweeps with a Python fitness function cannot be cached",
+        ));
+    }
+
+    let keys = parameter_sets
+        .iter()
+        .map(|parameters| SweepCache::key(parameters, options))
+        .collect::<Vec<_>>();
+    let mut trial_results = keys
+        .iter()
+        .map(|key| cache.as_ref().and_then(|cache| cache.load(key)))
+        .collect::<Vec<_>>();
+
+    let runs = parameter_sets
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| trial_results[idx].is_none())
+        .flat_map(|(idx, parameters)| {
+            (first_trial..trials)
+                .map(move |trial| (idx, parameters, derive_seed(parameters.seed, trial as u64)))
+        })
+        .collect::<Vec<_>>();
+
+    let cancelled = AtomicBool::new(false);
+    let fitness_error = FitnessError::default();
+    let completed = AtomicUsize::new(0);
+    let finished = AtomicBool::new(false);
+    let caller = std::thread::current();
+
+    let (results, callback_error) = std::thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            let _finished = OnDrop(|| {
+                finished.store(true, Ordering::Release);
+                caller.unpark();
+            });
+            runs.par_iter()
+                .map(|&(_, parameters, seed)| {
+                    if cancelled.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    // Rayon only re-raises a panic once every other trial has finished
+                    let _cancel_on_panic = OnDrop(|| {
+                        if std::thread::panicking() {
+                            cancelled.store(true, Ordering::Relaxed);
+                        }
+                    });
+                    let mut population = parameters.build_population(seed);
+                    if let Some(fitness) = fitness {
+                        population.set_fitness_function(PyFitnessFunction::new(
+                            fitness.clone(),
+                            fitness_error.clone(),
+                        ));
+                    }
+                    let mut trajectory = trajectories.then(|| Trajectory::new(trajectory_points));
+                    let result = population.run_with(limits, |population, stats| {
+                        if let Some(trajectory) = trajectory.as_mut() {
+                            trajectory.record(population, stats);
+                        }
+                        if cancelled.load(Ordering::Relaxed) || has_fitness_error(&fitness_error) {
+                            ControlFlow::Break(())
+                        } else {
+                            ControlFlow::Continue(())
+                        }
+                    });
+                    completed.fetch_add(1, Ordering::Relaxed);
+                    if result.outcome == RunOutcome::Stopped {
+                        return None;
+                    }
+
+                    if let Some(trajectory) = trajectory.as_mut() {
+                        trajectory.finish(&population);
+                    }
+                    let best_genes =
+                        population.population[population.stats().best_candidate].to_string();
+                    Some((result, best_genes, trajectory))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut callback_error = None;
+        let mut reported = None;
+        loop {
+            let is_finished = finished.load(Ordering::Acquire);
+            if py.check_signals().is_err() {
+                cancelled.store(true, Ordering::Relaxed);
+            }
+
+            let done = completed.load(Ordering::Relaxed);
+            if let (Some(progress), None) = (progress, &callback_error) {
+                if reported != Some(done) {
+                    reported = Some(done);
+                    if let Err(err) = progress.call1((done, runs.len())) {
+                        cancelled.store(true, Ordering::Relaxed);
+                        callback_error = Some(err);
+                    }
+                }
+            }
+            if is_finished {
+                break;
+            }
+            py.allow_threads(|| std::thread::park_timeout(PROGRESS_INTERVAL));
+        }
+
+        (worker.join(), callback_error)
+    });
+    let results = results.map_err(panic_error)?;
+
+    take_fitness_error(&fitness_error)?;
+    if let Some(err) = callback_error {
+        return Err(err);
+    }
+
+    let num_trials = trials - first_trial;
+    for (runs, results) in runs.chunks(num_trials).zip(results.chunks(num_trials)) {
+        let idx = runs[0].0;
+        let Some(results) = results.iter().cloned().collect::<Option<Vec<_>>>() else {
+            continue;
+        };
+        let run_results = || results.iter().map(|(result, _, _)| result);
+        let trials = TrialResults {
+            seeds: runs.iter().map(|&(_, _, seed)| seed).collect(),
+            generations_taken: run_results().map(|result| result.generations).collect(),
+            censored: run_results().map(RunResult::is_censored).collect(),
+            best_genes: results
+                .iter()
+                .map(|(_, best_genes, _)| best_genes.clone())
+                .collect(),
+            elapsed_seconds: run_results()
+                .map(|result| result.elapsed.as_secs_f64())
+                .collect(),
+            evaluations: run_results().map(|result| result.evaluations).collect(),
+            trajectories: trajectories.then(|| {
+                results
+                    .iter()
+                    .filter_map(|(_, _, trajectory)| trajectory.clone().map(Into::into))
+                    .collect()
+            }),
+        };
+
+        if let Some(cache) = cache {
+            if run_results().all(|result| result.outcome != RunOutcome::TimeCap) {
+                cache.store(&keys[idx], &trials)?;
+            }
+        }
+        trial_results[idx] = Some(trials);
+    }
+
+    Ok(parameter_sets
+        .into_iter()
+        .zip(trial_results)
+        .filter_map(|(parameters, trials)| Some(SimulationFrame::new(parameters, trials?)))
+        .collect())
+}
+
+/// Either the frames or, with `columnar`, their columns as laid out by `frame_columns`.
+fn into_results(py: Python, frames: Vec<SimulationFrame>, columnar: bool) -> PyResult<PyObject> {
+    if columnar {
+        Ok(columns::frame_columns(py, &frames)?.into_py(py))
+    } else {
+        Ok(frames.into_py(py))
     }
 }
 
-#[pyfunction]
+/// Simulates every mutation rate and population size in the half-open ranges, see
+/// `compute_generations_for_grid`.
+#[pyfunction(
+    trials = "1",
+    seed = "None",
+    max_generations = "None",
+    max_seconds = "None",
+    columnar = "false",
+    progress = "None",
+    cache_dir = "None",
+    trajectories = "false",
+    trajectory_points = "1000"
+)]
+#[allow(clippy::too_many_arguments)]
 fn compute_generations_for_dataset(
+    py: Python,
     target_term: &str,
     mutation_range: (usize, usize),
     population_range: (usize, usize),
     biased_scale: BiasedScaleStore,
-) -> Vec<SimulationFrame> {
-    let dataset = (mutation_range.0..mutation_range.1)
+    trials: usize,
+    seed: Option<u64>,
+    max_generations: Option<usize>,
+    max_seconds: Option<f64>,
+    columnar: bool,
+    progress: Option<&PyAny>,
+    cache_dir: Option<PathBuf>,
+    trajectories: bool,
+    trajectory_points: Option<usize>,
+) -> PyResult<PyObject> {
+    let options = SweepOptions {
+        trajectories,
+        trajectory_points,
+        ..SweepOptions::new(trials, max_generations, max_seconds, progress, cache_dir)?
+    };
+    let seed = seed.unwrap_or_else(Random::entropy_seed);
+    if population_range.0 < population_range.1 {
+        grid::validate_population_size(population_range.0)?;
+        grid::validate_scale(
+            &biased_scale,
+            target_term.chars().count(),
+            population_range.1 - 1,
+        )?;
+    }
+    let biased_scale = &biased_scale;
+
+    let parameter_sets = (mutation_range.0..mutation_range.1)
         .flat_map(|mutation_rate| {
-            (population_range.0..population_range.1)
-                .map(move |population_size| (mutation_rate, population_size))
+            (population_range.0..population_range.1).map(move |population_size| {
+                SimulationParameters {
+                    target_term: target_term.to_owned(),
+                    mutation_rate,
+                    population_size,
+                    biased_scale: biased_scale.clone(),
+                    selection: Default::default(),
+                    crossover: Default::default(),
+                    elitism: 0,
+                    seed,
+                }
+            })
         })
-        .collect::<Vec<_>>();
+        .collect();
 
-    dataset
-        .par_iter()
-        .map(|&(mutation_rate, population_size)| SimulationFrame {
-            population_size,
-            mutation_rate,
-            biased_scale: biased_scale.clone(),
-            generations_taken: total_generations_taken_to_simulate(
-                target_term,
-                mutation_rate,
-                population_size,
-                biased_scale.0.build_with_factor(biased_scale.1),
-            ),
-        })
-        .collect()
+    into_results(py, simulate_grid(py, parameter_sets, &options)?, columnar)
+}
+
+/// Simulates the Cartesian product of `grid`, a dict from parameter names to a value, a list
+/// of values or a `Values` range. Grid points without their own `seed` all use `seed`.
+///
+/// Each trial stops after `max_generations` generations or `max_seconds` of wall-clock time,
+/// whichever comes first, and is then reported as censored. With `columnar` the results come
+/// back as NumPy columns instead of `SimulationFrame`s, see `frame_columns`.
+///
+/// `progress(completed_trials, total_trials)` is called periodically while the sweep runs.
+/// Interrupting the sweep returns the grid points completed so far.
+///
+/// `fitness` is an optional Python fitness function shared by every grid point, called as
+/// described on `Population`. It runs under the GIL, so it serialises the trials it scores.
+///
+/// With `cache_dir`, every grid point is stored there under a hash of its parameters, the
+/// trial count, the caps and the library version, and later sweeps reuse it instead of
+/// simulating it again. Sweeps with a `fitness` function cannot be cached.
+///
+/// With `trajectories`, every trial also records the best and mean fitness and the diversity
+/// of its generations, downsampled to at most `trajectory_points` generations plus the last
+/// one, or every generation if `trajectory_points` is None.
+#[pyfunction(
+    trials = "1",
+    seed = "None",
+    max_generations = "None",
+    max_seconds = "None",
+    columnar = "false",
+    progress = "None",
+    fitness = "None",
+    cache_dir = "None",
+    trajectories = "false",
+    trajectory_points = "1000"
+)]
+#[allow(clippy::too_many_arguments)]
+fn compute_generations_for_grid(
+    py: Python,
+    grid: &PyDict,
+    trials: usize,
+    seed: Option<u64>,
+    max_generations: Option<usize>,
+    max_seconds: Option<f64>,
+    columnar: bool,
+    progress: Option<&PyAny>,
+    fitness: Option<PyObject>,
+    cache_dir: Option<PathBuf>,
+    trajectories: bool,
+    trajectory_points: Option<usize>,
+) -> PyResult<PyObject> {
+    let options = SweepOptions {
+        fitness: fitness.as_ref(),
+        trajectories,
+        trajectory_points,
+        ..SweepOptions::new(trials, max_generations, max_seconds, progress, cache_dir)?
+    };
+    let parameter_sets = grid::expand_grid(grid, seed.unwrap_or_else(Random::entropy_seed))?;
+
+    into_results(py, simulate_grid(py, parameter_sets, &options)?, columnar)
 }
 
 #[pymodule]
 fn data_visualization(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compute_generations_for_dataset, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_generations_for_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(shards::run_sharded_sweep, m)?)?;
+    m.add_function(wrap_pyfunction!(tuner::tune, m)?)?;
+    m.add_function(wrap_pyfunction!(comparison::compare_configurations, m)?)?;
+    m.add_function(wrap_pyfunction!(charts::render_heatmap, m)?)?;
+    m.add_function(wrap_pyfunction!(charts::render_convergence, m)?)?;
+    m.add_function(wrap_pyfunction!(charts::render_survival, m)?)?;
     m.add_class::<BiasedScaleType>()?;
+    m.add_class::<CrossoverType>()?;
+    m.add_class::<PySelection>()?;
+    m.add_class::<Values>()?;
     m.add_class::<SimulationFrame>()?;
+    m.add_class::<Objective>()?;
+    m.add_class::<TuningResult>()?;
+    m.add_class::<Comparison>()?;
+    m.add_class::<Statistic>()?;
+    m.add_class::<PyPopulation>()?;
+    m.add_class::<PyDna>()?;
+    m.add_class::<PyGenerationStats>()?;
+    m.add_class::<PyPopulationSnapshot>()?;
     Ok(())
 }
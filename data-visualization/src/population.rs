@@ -0,0 +1,676 @@
+use pyo3::{basic::CompareOp, exceptions::PyValueError, prelude::*, types::PyDict};
+use shakespeare_monkey_solver::{
+    dna::{decode_genes, encode_gene, Dna, UNKNOWN_GENE},
+    hall_of_fame::HallOfFame,
+    population::{FitnessEvaluation, Population},
+    snapshot::PopulationSnapshot,
+    stats::GenerationStats,
+    utils::{sampler::Sampler, RandomState},
+};
+use std::ops::ControlFlow;
+
+use crate::{
+    fitness::{has_fitness_error, take_fitness_error, FitnessError, PyFitnessFunction},
+    grid::{self, CrossoverType, PySelection},
+    protocols::{hash_of, required, richcmp},
+    run_limits, BiasedScaleStore, BiasedScaleType,
+};
+
+/// A candidate's genes with the fitness it was last evaluated to. Candidates pickle and
+/// compare by value, and `to_dict` gives the plain-Python form that `from_dict` reads back.
+#[pyclass(name = "Dna", module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PyDna {
+    #[pyo3(get)]
+    pub genes: String,
+    #[pyo3(get)]
+    pub fitness: usize,
+    #[pyo3(get)]
+    pub biased_fitness: f64,
+}
+
+impl From<&Dna> for PyDna {
+    fn from(candidate: &Dna) -> Self {
+        Self {
+            genes: candidate.to_string(),
+            fitness: candidate.fitness,
+            biased_fitness: candidate.biased_fitness,
+        }
+    }
+}
+
+#[pymethods]
+impl PyDna {
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("genes", &self.genes)?;
+        dict.set_item("fitness", self.fitness)?;
+        dict.set_item("biased_fitness", self.biased_fitness)?;
+        Ok(dict.into())
+    }
+
+    #[staticmethod]
+    fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        Ok(Self {
+            genes: required(dict, "genes")?,
+            fitness: required(dict, "fitness")?,
+            biased_fitness: required(dict, "biased_fitness")?,
+        })
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, (Py<PyDict>,))> {
+        let py = slf.py();
+        Ok((
+            py.get_type::<Self>().getattr("from_dict")?.into(),
+            (slf.to_dict(py)?,),
+        ))
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_of(&(&self.genes, self.fitness, self.biased_fitness.to_bits()))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Dna(genes={:?}, fitness={}, biased_fitness={:?})",
+            self.genes, self.fitness, self.biased_fitness
+        )
+    }
+}
+
+/// Statistics of one evaluated generation. They pickle and compare by value, and `to_dict`
+/// gives the plain-Python form that `from_dict` reads back.
+#[pyclass(
+    name = "GenerationStats",
+    module = "data_visualization.data_visualization"
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PyGenerationStats {
+    #[pyo3(get)]
+    pub generation: usize,
+    #[pyo3(get)]
+    pub best_candidate: usize,
+    #[pyo3(get)]
+    pub best_fitness: usize,
+    #[pyo3(get)]
+    pub mean_fitness: f64,
+    #[pyo3(get)]
+    pub worst_fitness: usize,
+    #[pyo3(get)]
+    pub solved: bool,
+}
+
+impl From<GenerationStats> for PyGenerationStats {
+    fn from(stats: GenerationStats) -> Self {
+        Self {
+            generation: stats.generation,
+            best_candidate: stats.best_candidate,
+            best_fitness: stats.best_fitness,
+            mean_fitness: stats.mean_fitness,
+            worst_fitness: stats.worst_fitness,
+            solved: stats.solved,
+        }
+    }
+}
+
+#[pymethods]
+impl PyGenerationStats {
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("generation", self.generation)?;
+        dict.set_item("best_candidate", self.best_candidate)?;
+        dict.set_item("best_fitness", self.best_fitness)?;
+        dict.set_item("mean_fitness", self.mean_fitness)?;
+        dict.set_item("worst_fitness", self.worst_fitness)?;
+        dict.set_item("solved", self.solved)?;
+        Ok(dict.into())
+    }
+
+    #[staticmethod]
+    fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        Ok(Self {
+            generation: required(dict, "generation")?,
+            best_candidate: required(dict, "best_candidate")?,
+            best_fitness: required(dict, "best_fitness")?,
+            mean_fitness: required(dict, "mean_fitness")?,
+            worst_fitness: required(dict, "worst_fitness")?,
+            solved: required(dict, "solved")?,
+        })
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, (Py<PyDict>,))> {
+        let py = slf.py();
+        Ok((
+            py.get_type::<Self>().getattr("from_dict")?.into(),
+            (slf.to_dict(py)?,),
+        ))
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    fn __hash__(&self) -> isize {
+        hash_of(&(
+            self.generation,
+            self.best_candidate,
+            self.best_fitness,
+            self.mean_fitness.to_bits(),
+            self.worst_fitness,
+            self.solved,
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "GenerationStats(generation={}, best_candidate={}, best_fitness={}, mean_fitness={:?}, \
+             worst_fitness={}, solved={})",
+            self.generation,
+            self.best_candidate,
+            self.best_fitness,
+            self.mean_fitness,
+            self.worst_fitness,
+            if self.solved { "True" } else { "False" }
+        )
+    }
+}
+
+fn sampler_name(sampler: Sampler) -> &'static str {
+    match sampler {
+        Sampler::Auto => "Auto",
+        Sampler::Cumulative => "Cumulative",
+        Sampler::Alias => "Alias",
+    }
+}
+
+fn parse_sampler(name: &str) -> PyResult<Sampler> {
+    match name {
+        "Auto" => Ok(Sampler::Auto),
+        "Cumulative" => Ok(Sampler::Cumulative),
+        "Alias" => Ok(Sampler::Alias),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown sampler {name:?}, expected one of Auto, Cumulative, Alias"
+        ))),
+    }
+}
+
+fn fitness_evaluation_name(fitness_evaluation: FitnessEvaluation) -> &'static str {
+    match fitness_evaluation {
+        FitnessEvaluation::Full => "Full",
+        FitnessEvaluation::Incremental => "Incremental",
+    }
+}
+
+fn parse_fitness_evaluation(name: &str) -> PyResult<FitnessEvaluation> {
+    match name {
+        "Full" => Ok(FitnessEvaluation::Full),
+        "Incremental" => Ok(FitnessEvaluation::Incremental),
+        _ => Err(PyValueError::new_err(format!(
+            "unknown fitness evaluation {name:?}, expected one of Full, Incremental"
+        ))),
+    }
+}
+
+/// Genes of `target_term`'s length, encoded, or an error naming the offending candidate.
+fn parse_genes(genes: &str, target_term: &str) -> PyResult<Vec<u8>> {
+    let encoded = genes.chars().map(encode_gene).collect::<Vec<_>>();
+    if encoded.len() != target_term.chars().count() || encoded.contains(&UNKNOWN_GENE) {
+        return Err(PyValueError::new_err(format!(
+            "genes {genes:?} must be as long as the target term and only use the charset"
+        )));
+    }
+    Ok(encoded)
+}
+
+/// A copy of a `Population` to resume it from with `Population.from_snapshot`. Snapshots
+/// pickle and compare by value, and `to_dict` gives the plain-Python form that `from_dict`
+/// reads back. Candidates are compared by their genes and fitness.
+#[pyclass(
+    name = "PopulationSnapshot",
+    module = "data_visualization.data_visualization"
+)]
+#[derive(Clone)]
+pub struct PyPopulationSnapshot(pub PopulationSnapshot);
+
+impl PartialEq for PyPopulationSnapshot {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.0, &other.0);
+        let candidates = |snapshot: &PopulationSnapshot| {
+            snapshot
+                .population
+                .iter()
+                .map(|candidate| {
+                    (
+                        candidate.genes.clone(),
+                        candidate.fitness,
+                        candidate.biased_fitness,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let hall_of_fame = |snapshot: &PopulationSnapshot| {
+            snapshot
+                .hall_of_fame
+                .as_ref()
+                .map(|hall_of_fame| (hall_of_fame.capacity(), hall_of_fame.entries().to_vec()))
+        };
+        a.generation == b.generation
+            && a.target_term == b.target_term
+            && a.random_state == b.random_state
+            && a.mutation_rate == b.mutation_rate
+            && a.biased_scale == b.biased_scale
+            && a.sampler == b.sampler
+            && a.selection == b.selection
+            && a.crossover == b.crossover
+            && a.elitism == b.elitism
+            && a.fitness_evaluation == b.fitness_evaluation
+            && candidates(a) == candidates(b)
+            && hall_of_fame(a) == hall_of_fame(b)
+    }
+}
+
+#[pymethods]
+impl PyPopulationSnapshot {
+    #[getter]
+    fn generation(&self) -> usize {
+        self.0.generation
+    }
+
+    #[getter]
+    fn target_term(&self) -> String {
+        self.0.target_term.clone()
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.0.random_state.seed
+    }
+
+    #[getter]
+    fn genes(&self) -> Vec<String> {
+        self.0.population.iter().map(Dna::to_string).collect()
+    }
+
+    /// Candidates are laid out as `genes`, `fitness` and `biased_fitness` columns, and the
+    /// hall of fame, if tracked, as its capacity and `(genes, fitness, score, generation)`
+    /// entries.
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let snapshot = &self.0;
+        let (scale_type, scale_factor) = BiasedScaleType::of(snapshot.biased_scale);
+        let dict = PyDict::new(py);
+        dict.set_item("generation", snapshot.generation)?;
+        dict.set_item("target_term", &snapshot.target_term)?;
+        dict.set_item("seed", snapshot.random_state.seed)?;
+        dict.set_item("random_position", snapshot.random_state.position)?;
+        dict.set_item("mutation_rate", snapshot.mutation_rate)?;
+        dict.set_item("scale_type", scale_type.into_py(py))?;
+        dict.set_item("scale_factor", scale_factor)?;
+        dict.set_item("sampler", sampler_name(snapshot.sampler))?;
+        dict.set_item("selection", PySelection(snapshot.selection).into_py(py))?;
+        dict.set_item(
+            "crossover",
+            CrossoverType::from(snapshot.crossover).into_py(py),
+        )?;
+        dict.set_item("elitism", snapshot.elitism)?;
+        dict.set_item(
+            "fitness_evaluation",
+            fitness_evaluation_name(snapshot.fitness_evaluation),
+        )?;
+        dict.set_item("genes", self.genes())?;
+        dict.set_item(
+            "fitness",
+            snapshot
+                .population
+                .iter()
+                .map(|candidate| candidate.fitness)
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "biased_fitness",
+            snapshot
+                .population
+                .iter()
+                .map(|candidate| candidate.biased_fitness)
+                .collect::<Vec<_>>(),
+        )?;
+        let hall_of_fame = snapshot
+            .hall_of_fame
+            .as_ref()
+            .map(|hall_of_fame| -> PyResult<Py<PyDict>> {
+                let entries = hall_of_fame
+                    .entries()
+                    .iter()
+                    .map(|entry| {
+                        (
+                            decode_genes(&entry.genes),
+                            entry.fitness,
+                            entry.score,
+                            entry.generation,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let dict = PyDict::new(py);
+                dict.set_item("capacity", hall_of_fame.capacity())?;
+                dict.set_item("entries", entries)?;
+                Ok(dict.into())
+            })
+            .transpose()?;
+        dict.set_item("hall_of_fame", hall_of_fame)?;
+        Ok(dict.into())
+    }
+
+    #[staticmethod]
+    fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let target_term = required::<String>(dict, "target_term")?;
+        let genes = required::<Vec<String>>(dict, "genes")?;
+        let fitness = required::<Vec<usize>>(dict, "fitness")?;
+        let biased_fitness = required::<Vec<f64>>(dict, "biased_fitness")?;
+        if genes.is_empty() {
+            return Err(PyValueError::new_err(
+                "a snapshot needs at least one candidate",
+            ));
+        }
+        if fitness.len() != genes.len() || biased_fitness.len() != genes.len() {
+            return Err(PyValueError::new_err(
+                "genes, fitness and biased_fitness must have one entry per candidate",
+            ));
+        }
+        let population = genes
+            .iter()
+            .zip(fitness)
+            .zip(biased_fitness)
+            .map(|((genes, fitness), biased_fitness)| {
+                Ok(Dna {
+                    fitness,
+                    biased_fitness,
+                    ..Dna::from_genes(parse_genes(genes, &target_term)?)
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let hall_of_fame = required::<Option<&PyDict>>(dict, "hall_of_fame")?
+            .map(|dict| -> PyResult<HallOfFame> {
+                let mut hall_of_fame = HallOfFame::new(required(dict, "capacity")?);
+                // Offering the entries best first rebuilds the same archive
+                for (genes, fitness, score, generation) in
+                    required::<Vec<(String, usize, f64, usize)>>(dict, "entries")?
+                {
+                    let candidate = Dna {
+                        fitness,
+                        ..Dna::from_genes(parse_genes(&genes, &target_term)?)
+                    };
+                    hall_of_fame.offer(&candidate, score, generation);
+                }
+                Ok(hall_of_fame)
+            })
+            .transpose()?;
+
+        let biased_scale: BiasedScaleStore = (
+            required(dict, "scale_type")?,
+            required(dict, "scale_factor")?,
+        );
+        Ok(Self(PopulationSnapshot {
+            generation: required(dict, "generation")?,
+            random_state: RandomState {
+                seed: required(dict, "seed")?,
+                position: required(dict, "random_position")?,
+            },
+            mutation_rate: required(dict, "mutation_rate")?,
+            biased_scale: biased_scale.0.build_with_factor(biased_scale.1),
+            sampler: parse_sampler(required(dict, "sampler")?)?,
+            selection: required::<PySelection>(dict, "selection")?.0,
+            crossover: required::<CrossoverType>(dict, "crossover")?.into(),
+            elitism: required(dict, "elitism")?,
+            fitness_evaluation: parse_fitness_evaluation(required(dict, "fitness_evaluation")?)?,
+            population,
+            hall_of_fame,
+            target_term,
+        }))
+    }
+
+    fn __reduce__(slf: PyRef<Self>) -> PyResult<(PyObject, (Py<PyDict>,))> {
+        let py = slf.py();
+        Ok((
+            py.get_type::<Self>().getattr("from_dict")?.into(),
+            (slf.to_dict(py)?,),
+        ))
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        richcmp(self, other, op)
+    }
+
+    /// Leaves out the settings, which rarely differ between snapshots of one run.
+    fn __hash__(&self) -> isize {
+        let snapshot = &self.0;
+        hash_of(&(
+            snapshot.generation,
+            &snapshot.target_term,
+            snapshot.random_state.seed,
+            snapshot.random_state.position,
+            snapshot
+                .population
+                .iter()
+                .map(|candidate| (&candidate.genes, candidate.fitness))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PopulationSnapshot(generation={}, target_term={:?}, population_size={})",
+            self.0.generation,
+            self.0.target_term,
+            self.0.population.len()
+        )
+    }
+}
+
+/// A `Population` that is evaluated after every generation, so its candidates and stats
+/// can be inspected between steps.
+///
+/// `fitness`, if given, is called with the genes of every generation as a list of strings
+/// and returns a list of non-negative floats, used instead of the biased scale to select
+/// parents. Exceptions it raises propagate out of the call that evaluated the generation.
+///
+/// Raises ValueError for an empty population and, without `fitness`, for scales that
+/// `Grid` rejects too.
+#[pyclass(name = "Population", module = "data_visualization.data_visualization")]
+pub struct PyPopulation(Population, FitnessError);
+
+impl PyPopulation {
+    fn evaluated(mut population: Population, fitness: Option<PyObject>) -> PyResult<Self> {
+        let fitness_error = FitnessError::default();
+        if let Some(fitness) = fitness {
+            population.set_fitness_function(PyFitnessFunction::new(fitness, fitness_error.clone()));
+        }
+        population.compute_biased_fitness();
+        take_fitness_error(&fitness_error)?;
+        Ok(Self(population, fitness_error))
+    }
+}
+
+#[pymethods]
+impl PyPopulation {
+    #[new]
+    #[args(seed = "None", fitness = "None")]
+    fn new(
+        target_term: String,
+        mutation_rate: usize,
+        population_size: usize,
+        biased_scale: BiasedScaleStore,
+        seed: Option<u64>,
+        fitness: Option<PyObject>,
+    ) -> PyResult<Self> {
+        grid::validate_population_size(population_size)?;
+        // A fitness function replaces the biased scale
+        if fitness.is_none() {
+            grid::validate_scale(&biased_scale, target_term.chars().count(), population_size)?;
+        }
+        let biased_scale = biased_scale.0.build_with_factor(biased_scale.1);
+        let population = match seed {
+            Some(seed) => Population::with_seed(
+                target_term,
+                mutation_rate,
+                population_size,
+                biased_scale,
+                seed,
+            ),
+            None => Population::new(target_term, mutation_rate, population_size, biased_scale),
+        };
+        Self::evaluated(population, fitness)
+    }
+
+    #[staticmethod]
+    #[args(fitness = "None")]
+    fn from_snapshot(snapshot: PyPopulationSnapshot, fitness: Option<PyObject>) -> PyResult<Self> {
+        Self::evaluated(Population::from_snapshot(snapshot.0), fitness)
+    }
+
+    fn snapshot(&self) -> PyPopulationSnapshot {
+        PyPopulationSnapshot(self.0.snapshot())
+    }
+
+    /// Advances `generations` generations, stopping early once a candidate matches the target.
+    /// Signals such as Ctrl-C are handled between generations.
+    #[args(generations = "1")]
+    fn step(&mut self, py: Python, generations: usize) -> PyResult<PyGenerationStats> {
+        for _ in 0..generations {
+            if self.0.stats().solved {
+                break;
+            }
+            self.0.update_generation();
+            self.0.compute_biased_fitness();
+            take_fitness_error(&self.1)?;
+            py.check_signals()?;
+        }
+        Ok(self.0.stats().into())
+    }
+
+    /// Steps until solved, until `max_generations` is reached or until `max_seconds` have
+    /// passed, returning the generation. Like `step`, it can be interrupted by signals.
+    #[args(max_generations = "None", max_seconds = "None")]
+    fn run(
+        &mut self,
+        py: Python,
+        max_generations: Option<usize>,
+        max_seconds: Option<f64>,
+    ) -> PyResult<usize> {
+        let limits = run_limits(max_generations, max_seconds)?;
+        let mut interrupted = Ok(());
+        let result = self.0.run_with(&limits, |_, _| {
+            if has_fitness_error(&self.1) {
+                return ControlFlow::Break(());
+            }
+            interrupted = py.check_signals();
+            if interrupted.is_err() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        take_fitness_error(&self.1)?;
+        interrupted?;
+        Ok(result.generations)
+    }
+
+    fn stats(&self) -> PyGenerationStats {
+        self.0.stats().into()
+    }
+
+    /// Probability that two random candidates differ at a locus, averaged over all loci.
+    fn diversity(&self) -> f64 {
+        self.0.diversity()
+    }
+
+    fn best(&self) -> Option<PyDna> {
+        self.0
+            .population
+            .get(self.0.stats().best_candidate)
+            .map(PyDna::from)
+    }
+
+    fn candidates(&self) -> Vec<PyDna> {
+        self.0.population.iter().map(PyDna::from).collect()
+    }
+
+    fn genes(&self) -> Vec<String> {
+        self.0.population.iter().map(Dna::to_string).collect()
+    }
+
+    fn fitness(&self) -> Vec<usize> {
+        self.0
+            .population
+            .iter()
+            .map(|candidate| candidate.fitness)
+            .collect()
+    }
+
+    fn track_hall_of_fame(&mut self, capacity: usize) -> PyResult<()> {
+        self.0.track_hall_of_fame(capacity);
+        self.0.compute_biased_fitness();
+        take_fitness_error(&self.1)
+    }
+
+    /// Archived `(genes, fitness, score, generation)` tuples, best first. The archive ranks
+    /// by `score`, which is the fitness unless `fitness` was given.
+    fn hall_of_fame(&self) -> Vec<(String, usize, f64, usize)> {
+        self.0
+            .hall_of_fame
+            .iter()
+            .flat_map(|hall_of_fame| hall_of_fame.entries())
+            .map(|entry| {
+                (
+                    decode_genes(&entry.genes),
+                    entry.fitness,
+                    entry.score,
+                    entry.generation,
+                )
+            })
+            .collect()
+    }
+
+    #[getter]
+    fn generation(&self) -> usize {
+        self.0.generation
+    }
+
+    #[getter]
+    fn seed(&self) -> u64 {
+        self.0.seed
+    }
+
+    #[getter]
+    fn target_term(&self) -> String {
+        self.0.target_term.clone()
+    }
+
+    #[getter]
+    fn population_size(&self) -> usize {
+        self.0.population.len()
+    }
+
+    #[getter]
+    fn get_mutation_rate(&self) -> usize {
+        self.0.mutation_rate
+    }
+
+    #[setter]
+    fn set_mutation_rate(&mut self, mutation_rate: usize) {
+        self.0.mutation_rate = mutation_rate;
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Population(target_term={:?}, generation={}, population_size={}, seed={})",
+            self.0.target_term,
+            self.0.generation,
+            self.0.population.len(),
+            self.0.seed
+        )
+    }
+}
@@ -0,0 +1,81 @@
+use pyo3::{basic::CompareOp, exceptions::PyKeyError, prelude::*, types::PyDict, PyClass};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+/// Gives a fieldless `#[pyclass]` enum a string round-trip through `str` and `from_str`,
+/// hashing consistent with its equality, and pickling by variant name.
+macro_rules! enum_protocols {
+    ($name:ident { $($variant:ident),* $(,)? }) => {
+        #[pyo3::pymethods]
+        impl $name {
+            /// Variant named `name`, the inverse of `str`.
+            #[staticmethod]
+            fn from_str(name: &str) -> pyo3::PyResult<Self> {
+                match name {
+                    $(stringify!($variant) => Ok($name::$variant),)*
+                    _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        concat!("unknown ", stringify!($name), " {:?}, expected one of {}"),
+                        name,
+                        [$(stringify!($variant)),*].join(", ")
+                    ))),
+                }
+            }
+
+            fn __str__(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                }
+            }
+
+            fn __hash__(&self) -> isize {
+                self.clone() as isize
+            }
+
+            // Takes no `py` so that pyo3 exports it without argument parsing, which trips
+            // over the null argument array pickle calls it with
+            fn __reduce__(
+                slf: pyo3::PyRef<Self>,
+            ) -> pyo3::PyResult<(pyo3::PyObject, (&'static str,))> {
+                Ok((
+                    slf.py().get_type::<Self>().getattr("from_str")?.into(),
+                    (slf.__str__(),),
+                ))
+            }
+        }
+    };
+}
+
+pub(crate) use enum_protocols;
+
+/// Value of `key` in a dict built by one of the `to_dict` methods.
+pub fn required<'a, T: FromPyObject<'a>>(dict: &'a PyDict, key: &str) -> PyResult<T> {
+    dict.get_item(key)
+        .ok_or_else(|| PyKeyError::new_err(key.to_owned()))?
+        .extract()
+}
+
+/// `__hash__` of a value whose Python equality is its Rust equality.
+pub fn hash_of<T: Hash + ?Sized>(value: &T) -> isize {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish() as isize
+}
+
+/// `==` and `!=` between two instances of the same class, leaving other comparisons and
+/// other types to Python.
+pub fn richcmp<T>(value: &T, other: &PyAny, op: CompareOp) -> PyObject
+where
+    T: PyClass + PartialEq,
+{
+    let py = other.py();
+    let Ok(other) = other.extract::<PyRef<T>>() else {
+        return py.NotImplemented();
+    };
+    match op {
+        CompareOp::Eq => (*value == *other).into_py(py),
+        CompareOp::Ne => (*value != *other).into_py(py),
+        _ => py.NotImplemented(),
+    }
+}
@@ -0,0 +1,288 @@
+use pyo3::{
+    exceptions::{PyKeyboardInterrupt, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use serde::{Deserialize, Serialize};
+use shakespeare_monkey_solver::utils::random::Random;
+use std::{
+    fs::{self, File, TryLockError},
+    io,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    cache::{fnv1a, write_atomically, SweepCache},
+    grid::{expand_grid, SimulationParameters},
+    into_results, simulate_grid, SimulationFrame, SweepOptions, TrialResults,
+};
+
+/// How often a worker checks on shards that other workers are simulating.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Identifies the sweep a results directory belongs to. Every worker must derive the same
+/// manifest from its arguments to join the sweep.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    /// Hash of the cache keys of every grid point, in grid order.
+    fingerprint: String,
+    seed: u64,
+    num_points: usize,
+    shard_size: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ShardEntry {
+    fingerprint: String,
+    trials: Vec<TrialResults>,
+}
+
+/// Directory shared by the workers of a sharded sweep. `sweep.json` holds its `Manifest`,
+/// `shard-NNNNN.json` the results of every finished shard, and a worker simulating a shard
+/// holds an exclusive lock on `shard-NNNNN.lock`. The operating system releases the locks of
+/// workers that crash, so other workers take their shards over.
+struct ResultsDir {
+    directory: PathBuf,
+    manifest: Manifest,
+}
+
+impl ResultsDir {
+    /// Opens `directory`, creating its manifest if this is the sweep's first worker. Without
+    /// `seed` the sweep keeps the seed of its first worker.
+    fn open(
+        directory: &Path,
+        grid: &PyDict,
+        seed: Option<u64>,
+        shard_size: usize,
+        options: &SweepOptions,
+    ) -> PyResult<(Self, Vec<SimulationParameters>)> {
+        fs::create_dir_all(directory)?;
+        // Held while the manifest is read or written, so workers starting together agree on it
+        let lock = File::create(directory.join("sweep.lock"))?;
+        lock.lock()?;
+
+        let path = directory.join("sweep.json");
+        let existing = match fs::read(&path) {
+            Ok(bytes) => Some(serde_json::from_slice::<Manifest>(&bytes).map_err(io::Error::from)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let seed = seed
+            .or(existing.as_ref().map(|manifest| manifest.seed))
+            .unwrap_or_else(Random::entropy_seed);
+        let points = expand_grid(grid, seed)?;
+        let keys = points
+            .iter()
+            .map(|parameters| SweepCache::key(parameters, options))
+            .collect::<Vec<_>>();
+        let manifest = Manifest {
+            fingerprint: format!("{:016x}", fnv1a(keys.join("\n").as_bytes())),
+            seed,
+            num_points: points.len(),
+            shard_size,
+        };
+
+        match existing {
+            Some(existing) if existing != manifest => {
+                return Err(PyValueError::new_err(format!(
+                    "{} holds a different sweep, pass the grid, seed, shard size, trials, caps and \
+                     trajectory settings it was started with or use another results_dir",
+                    directory.display()
+                )))
+            }
+            Some(_) => {}
+            None => write_atomically(
+                &path,
+                &serde_json::to_vec_pretty(&manifest).map_err(io::Error::from)?,
+            )?,
+        }
+        let results = Self {
+            directory: directory.to_owned(),
+            manifest,
+        };
+        Ok((results, points))
+    }
+
+    fn num_shards(&self) -> usize {
+        self.manifest.num_points.div_ceil(self.manifest.shard_size)
+    }
+
+    fn points(&self, shard: usize) -> Range<usize> {
+        let start = shard * self.manifest.shard_size;
+        start..(start + self.manifest.shard_size).min(self.manifest.num_points)
+    }
+
+    fn path(&self, shard: usize, extension: &str) -> PathBuf {
+        self.directory.join(format!("shard-{shard:05}.{extension}"))
+    }
+
+    fn is_finished(&self, shard: usize) -> bool {
+        self.path(shard, "json").exists()
+    }
+
+    /// The shard's lock, unless another worker holds it.
+    fn try_claim(&self, shard: usize) -> io::Result<Option<File>> {
+        let lock = File::options()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(self.path(shard, "lock"))?;
+        match lock.try_lock() {
+            Ok(()) => Ok(Some(lock)),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(err)) => Err(err),
+        }
+    }
+
+    /// Removes the lock file of a finished shard. Workers waiting on it still check that the
+    /// shard is unfinished once they hold the lock, so removing it while they wait is safe.
+    /// A worker that held the lock before may have removed it already, or one that claimed it
+    /// since may have created it anew, which is as safe to remove once the shard is finished.
+    fn release(&self, shard: usize, lock: File) -> io::Result<()> {
+        match fs::remove_file(self.path(shard, "lock")) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        drop(lock);
+        Ok(())
+    }
+
+    fn store(&self, shard: usize, trials: Vec<TrialResults>) -> io::Result<()> {
+        let entry = ShardEntry {
+            fingerprint: self.manifest.fingerprint.clone(),
+            trials,
+        };
+        write_atomically(&self.path(shard, "json"), &serde_json::to_vec(&entry)?)
+    }
+
+    fn load(&self, shard: usize) -> io::Result<Vec<TrialResults>> {
+        let entry = serde_json::from_slice::<ShardEntry>(&fs::read(self.path(shard, "json"))?)?;
+        if entry.fingerprint != self.manifest.fingerprint {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} belongs to a different sweep",
+                    self.path(shard, "json").display()
+                ),
+            ));
+        }
+        Ok(entry.trials)
+    }
+}
+
+/// Simulates `grid` like `compute_generations_for_grid`, in shards of `shard_size` grid
+/// points whose results are written to `results_dir` as they finish.
+///
+/// Running the same sweep again with the same `results_dir` resumes it, skipping the
+/// finished shards. Several processes on one machine can run the same sweep at once: each
+/// claims unfinished shards through lock files in `results_dir`, then waits for the shards
+/// the others are simulating, taking over those of processes that died. Every process
+/// returns the whole sweep. Without `seed`, processes joining a sweep use the seed it was
+/// started with.
+///
+/// `progress(finished_shards, total_shards)` is called whenever more shards are found
+/// finished. Interrupting the sweep raises KeyboardInterrupt, keeping the finished shards.
+#[pyfunction(
+    shard_size = "16",
+    trials = "1",
+    seed = "None",
+    max_generations = "None",
+    max_seconds = "None",
+    columnar = "false",
+    progress = "None",
+    trajectories = "false",
+    trajectory_points = "1000"
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn run_sharded_sweep(
+    py: Python,
+    grid: &PyDict,
+    results_dir: PathBuf,
+    shard_size: usize,
+    trials: usize,
+    seed: Option<u64>,
+    max_generations: Option<usize>,
+    max_seconds: Option<f64>,
+    columnar: bool,
+    progress: Option<&PyAny>,
+    trajectories: bool,
+    trajectory_points: Option<usize>,
+) -> PyResult<PyObject> {
+    if shard_size == 0 {
+        return Err(PyValueError::new_err("shard_size must be at least 1"));
+    }
+    let options = SweepOptions {
+        trajectories,
+        trajectory_points,
+        ..SweepOptions::new(trials, max_generations, max_seconds, None, None)?
+    };
+    let (results, points) = ResultsDir::open(&results_dir, grid, seed, shard_size, &options)?;
+
+    let num_shards = results.num_shards();
+    let mut finished = vec![false; num_shards];
+    let mut reported = None;
+    loop {
+        let mut waiting = false;
+        for shard in 0..num_shards {
+            if finished[shard] {
+                continue;
+            }
+            if !results.is_finished(shard) {
+                let Some(lock) = results.try_claim(shard)? else {
+                    waiting = true;
+                    continue;
+                };
+                // Another worker may have finished the shard before it was claimed
+                if !results.is_finished(shard) {
+                    let range = results.points(shard);
+                    let num_points = range.len();
+                    let frames = simulate_grid(py, points[range].to_vec(), &options)?;
+                    if frames.len() < num_points {
+                        return Err(PyKeyboardInterrupt::new_err(format!(
+                            "sweep was interrupted, run it again to resume from its finished \
+                             shards in {}",
+                            results_dir.display()
+                        )));
+                    }
+                    results.store(shard, frames.into_iter().map(Into::into).collect())?;
+                }
+                results.release(shard, lock)?;
+            }
+            finished[shard] = true;
+
+            let done = finished.iter().filter(|&&finished| finished).count();
+            if let Some(progress) = progress.filter(|_| reported != Some(done)) {
+                reported = Some(done);
+                progress.call1((done, num_shards))?;
+            }
+        }
+        if !waiting {
+            break;
+        }
+        py.allow_threads(|| std::thread::sleep(POLL_INTERVAL));
+        py.check_signals()?;
+    }
+
+    let mut frames = Vec::with_capacity(points.len());
+    for shard in 0..num_shards {
+        let range = results.points(shard);
+        let trials = results.load(shard)?;
+        if trials.len() != range.len() {
+            return Err(PyValueError::new_err(format!(
+                "shard {shard} holds {} grid points, expected {}",
+                trials.len(),
+                range.len()
+            )));
+        }
+        frames.extend(
+            points[range]
+                .iter()
+                .cloned()
+                .zip(trials)
+                .map(|(parameters, trials)| SimulationFrame::new(parameters, trials)),
+        );
+    }
+    into_results(py, frames, columnar)
+}
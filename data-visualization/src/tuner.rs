@@ -0,0 +1,230 @@
+use pyo3::{
+    exceptions::{PyKeyboardInterrupt, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+use shakespeare_monkey_solver::{statistics::TrialSummary, utils::random::Random};
+use std::{collections::HashSet, path::PathBuf};
+
+use crate::{
+    grid::{Grid, SimulationParameters},
+    protocols::enum_protocols,
+    simulate_grid, SimulationFrame, SweepOptions,
+};
+
+/// What the tuner minimises.
+#[pyclass(module = "data_visualization.data_visualization")]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Objective {
+    /// Generations taken to solve the target.
+    Generations,
+    /// Fitness evaluations taken to solve the target, as counted by the run.
+    Evaluations,
+}
+
+enum_protocols!(Objective {
+    Generations,
+    Evaluations
+});
+
+impl Objective {
+    fn samples<'a>(&self, frame: &'a SimulationFrame) -> &'a [usize] {
+        match self {
+            Objective::Generations => &frame.generations_taken,
+            Objective::Evaluations => &frame.evaluations,
+        }
+    }
+}
+
+/// A configuration's standing in a round: fewer censored trials first, then the lower
+/// mean objective. Censored trials only give lower bounds, so their means are not compared
+/// against solved ones.
+fn rank_key(objective: &Objective, frame: &SimulationFrame) -> (usize, f64) {
+    let num_censored = frame.censored.iter().filter(|&&censored| censored).count();
+    (
+        num_censored,
+        TrialSummary::from_samples(objective.samples(frame)).mean,
+    )
+}
+
+/// Draws up to `num` distinct points of `grid` uniformly at random, in grid order.
+fn sample_points(grid: &Grid, num: usize, rng: &mut Random) -> Vec<SimulationParameters> {
+    let num_points = grid.num_points();
+    let mut indices = if num >= num_points {
+        (0..num_points).collect::<Vec<_>>()
+    } else {
+        let mut picked = HashSet::with_capacity(num);
+        while picked.len() < num {
+            picked.insert(rng.gen_range_usize(0..num_points));
+        }
+        picked.into_iter().collect()
+    };
+    indices.sort_unstable();
+    indices.into_iter().map(|idx| grid.point(idx)).collect()
+}
+
+#[pyclass(module = "data_visualization.data_visualization")]
+pub struct TuningResult {
+    /// Best configuration, as a grid accepted by `compute_generations_for_grid`.
+    #[pyo3(get)]
+    parameters: Py<PyDict>,
+    /// Trials of the best configuration in the final round.
+    #[pyo3(get)]
+    frame: Py<SimulationFrame>,
+    #[pyo3(get)]
+    objective: Objective,
+    /// Objective over the final round's trials of the best configuration, see `TrialSummary`.
+    #[pyo3(get)]
+    mean: f64,
+    #[pyo3(get)]
+    confidence_interval: (f64, f64),
+    #[pyo3(get)]
+    num_censored: usize,
+    /// `(configurations, trials)` of every completed round, counting the trials carried over
+    /// from earlier rounds.
+    #[pyo3(get)]
+    rounds: Vec<(usize, usize)>,
+}
+
+#[pymethods]
+impl TuningResult {
+    fn __repr__(&self, py: Python) -> String {
+        format!(
+            "TuningResult(parameters={}, objective={:?}, mean={}, confidence_interval={:?}, \
+             num_censored={}, rounds={:?})",
+            self.parameters.as_ref(py),
+            self.objective,
+            self.mean,
+            self.confidence_interval,
+            self.num_censored,
+            self.rounds
+        )
+    }
+}
+
+/// Searches `space`, a grid as accepted by `compute_generations_for_grid`, for the
+/// configuration minimising `objective`.
+///
+/// `configurations` points of the grid are drawn at random. Without `min_trials` each is
+/// simulated `trials` times. With `min_trials`, successive halving starts every drawn point
+/// at `min_trials` trials, then keeps the best `1 / eta` of them and multiplies their trials
+/// by `eta` until `trials` is reached. Configurations with fewer censored trials rank first,
+/// then those with the lower mean objective. Each round only simulates the trials its
+/// configurations do not have yet.
+///
+/// The returned `TuningResult` holds the best configuration with the 95% confidence interval
+/// of its mean objective over the final round's trials. Interrupting the tuner returns the
+/// best configuration of the last completed round. An empty `space` raises ValueError.
+#[pyfunction(
+    configurations = "32",
+    trials = "9",
+    min_trials = "None",
+    eta = "3",
+    objective = "Objective::Generations",
+    seed = "None",
+    max_generations = "None",
+    max_seconds = "None",
+    progress = "None",
+    cache_dir = "None"
+)]
+#[allow(clippy::too_many_arguments)]
+pub fn tune(
+    py: Python,
+    space: &PyDict,
+    configurations: usize,
+    trials: usize,
+    min_trials: Option<usize>,
+    eta: usize,
+    objective: Objective,
+    seed: Option<u64>,
+    max_generations: Option<usize>,
+    max_seconds: Option<f64>,
+    progress: Option<&PyAny>,
+    cache_dir: Option<PathBuf>,
+) -> PyResult<TuningResult> {
+    let min_trials = min_trials.unwrap_or(trials);
+    if configurations == 0 {
+        return Err(PyValueError::new_err("configurations must be at least 1"));
+    }
+    if min_trials == 0 || min_trials > trials {
+        return Err(PyValueError::new_err(
+            "min_trials must be between 1 and trials",
+        ));
+    }
+    if eta < 2 {
+        return Err(PyValueError::new_err("eta must be at least 2"));
+    }
+
+    let mut options = SweepOptions::new(
+        min_trials,
+        max_generations,
+        max_seconds,
+        progress,
+        cache_dir,
+    )?;
+    let seed = seed.unwrap_or_else(Random::entropy_seed);
+    let grid = Grid::parse(space, seed)?;
+    if grid.num_points() == 0 {
+        return Err(PyValueError::new_err(
+            "space holds no configurations, give every parameter at least one value",
+        ));
+    }
+    let mut candidates = sample_points(&grid, configurations, &mut Random::from_seed(seed));
+
+    let mut rounds = Vec::new();
+    // Configurations still in the race, best first
+    let mut survivors = Vec::<SimulationFrame>::new();
+    loop {
+        let num_candidates = candidates.len();
+        let frames = simulate_grid(py, candidates, &options)?;
+        if frames.len() < num_candidates {
+            break;
+        }
+        rounds.push((num_candidates, options.trials));
+
+        // The new trials' frames are in the order of the survivors they extend
+        let mut frames = if survivors.is_empty() {
+            frames
+        } else {
+            std::mem::take(&mut survivors)
+                .into_iter()
+                .zip(frames)
+                .map(|(frame, later)| frame.extended(later))
+                .collect()
+        };
+        frames.sort_by(|a, b| {
+            let (a, b) = (rank_key(&objective, a), rank_key(&objective, b));
+            a.0.cmp(&b.0).then(a.1.total_cmp(&b.1))
+        });
+        let is_final = options.trials == trials;
+        frames.truncate(if is_final {
+            1
+        } else {
+            frames.len().div_ceil(eta)
+        });
+        candidates = frames
+            .iter()
+            .map(|frame| frame.parameters.clone())
+            .collect();
+        survivors = frames;
+        if is_final {
+            break;
+        }
+        options.first_trial = options.trials;
+        options.trials = (options.trials * eta).min(trials);
+    }
+
+    let best = survivors.into_iter().next().ok_or_else(|| {
+        PyKeyboardInterrupt::new_err("tuning was interrupted during its first round")
+    })?;
+    let summary = TrialSummary::from_samples(objective.samples(&best));
+    Ok(TuningResult {
+        parameters: best.parameters.to_dict(py)?,
+        num_censored: best.censored.iter().filter(|&&censored| censored).count(),
+        frame: Py::new(py, best)?,
+        objective,
+        mean: summary.mean,
+        confidence_interval: summary.confidence_interval,
+        rounds,
+    })
+}
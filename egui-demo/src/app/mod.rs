@@ -1,6 +1,7 @@
 mod state;
 
 use eframe::egui;
+use shakespeare_monkey_solver::dna::decode_genes;
 use state::{
     biased_scale::BiasedScaleStore::*, population::PopulationStore,
     population_builder::PopulationBuilder,
@@ -55,7 +56,7 @@ impl eframe::App for TemplateApp {
                 egui::plot::Plot::new("best-generation-fitness-plot")
                     .include_x(0.0)
                     .include_y(0.0)
-                    .include_y(simulation.target_term.len() as f64)
+                    .include_y(simulation.num_genes() as f64)
                     .view_aspect(2.0)
                     .allow_drag(false)
                     .allow_zoom(false)
@@ -71,6 +72,30 @@ impl eframe::App for TemplateApp {
                         ));
                     });
             });
+
+            if let Some(hall_of_fame) = &simulation.hall_of_fame {
+                egui::Window::new("Hall of Fame").show(ctx, |ui| {
+                    egui::Grid::new("hall-of-fame-grid")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for entry in hall_of_fame.entries() {
+                                ui.label(
+                                    egui::RichText::new(decode_genes(&entry.genes)).monospace(),
+                                );
+                                ui.label(
+                                    egui::RichText::new(entry.fitness.to_string())
+                                        .color(egui::Color32::GOLD),
+                                );
+                                ui.label(
+                                    egui::RichText::new(format!("Gen {}", entry.generation))
+                                        .color(egui::Color32::LIGHT_GRAY),
+                                );
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
         }
 
         egui::SidePanel::left("config-panel")
@@ -216,8 +241,9 @@ impl eframe::App for TemplateApp {
                     ui.spacing_mut().item_spacing.x = 0.0;
                     let best_candidate = &simulation.population[simulation.best_candidate];
 
-                    for (idx, token) in simulation.target_term.char_indices() {
-                        let mut label = egui::RichText::new(best_candidate.genes[idx]).underline();
+                    for (idx, &token) in simulation.target_genes().iter().enumerate() {
+                        let mut label =
+                            egui::RichText::new(best_candidate.gene_char(idx)).underline();
                         if token == best_candidate.genes[idx] {
                             label = label.color(egui::Color32::GOLD);
                         }
@@ -232,8 +258,8 @@ impl eframe::App for TemplateApp {
                         ui.horizontal(|ui| {
                             ui.spacing_mut().item_spacing.x = 0.0;
 
-                            for (idx, token) in simulation.target_term.char_indices() {
-                                let mut label = egui::RichText::new(candidate.genes[idx]);
+                            for (idx, &token) in simulation.target_genes().iter().enumerate() {
+                                let mut label = egui::RichText::new(candidate.gene_char(idx));
                                 if token == candidate.genes[idx] {
                                     label = label.color(egui::Color32::LIGHT_GREEN);
                                 }
@@ -27,7 +27,7 @@ impl PopulationStore {
                 self.best_candidate = idx;
             }
 
-            if candidate.fitness == self.store.target_term.len() {
+            if candidate.fitness == self.store.num_genes() {
                 self.has_finished = true;
             }
         }
@@ -1,6 +1,8 @@
 use super::{biased_scale::BiasedScaleStore, population::PopulationStore};
 use shakespeare_monkey_solver::population::Population;
 
+const HALL_OF_FAME_SIZE: usize = 10;
+
 #[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
 pub struct PopulationBuilder {
     pub target_term: String,
@@ -24,13 +26,16 @@ impl Default for PopulationBuilder {
 
 impl PopulationBuilder {
     pub fn build_simulation(&mut self) -> PopulationStore {
+        let mut population = Population::new(
+            self.target_term.clone(),
+            self.mutation_rate,
+            self.population_size,
+            self.biased_scale.build_with_factor(self.scale_factor),
+        );
+        population.track_hall_of_fame(HALL_OF_FAME_SIZE);
+
         PopulationStore {
-            store: Population::new(
-                self.target_term.clone(),
-                self.mutation_rate,
-                self.population_size,
-                self.biased_scale.build_with_factor(self.scale_factor),
-            ),
+            store: population,
             generation_counter: 0,
             best_candidate: 0,
             has_finished: false,
@@ -0,0 +1,97 @@
+use plotters::style::FontStyle;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use crate::ChartError;
+
+/// Tried in order when no font is given: DejaVu or Liberation on the common Linux
+/// distributions, then the default sans-serif of macOS and Windows.
+const SYSTEM_FONTS: [&str; 9] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu-sans-fonts/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+    "/usr/share/fonts/liberation-sans/LiberationSans-Regular.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+];
+
+/// Fira Sans (SIL Open Font License 1.1, see `assets/FiraSans-LICENSE.txt`), used where none
+/// of the system fonts exist, as on most headless machines.
+const BUNDLED_FONT: &[u8] = include_bytes!("../assets/FiraSans-Regular.ttf");
+
+#[derive(Clone, Debug, PartialEq)]
+enum Source {
+    File(PathBuf),
+    Bundled,
+}
+
+/// The font chart text is currently laid out with.
+static REGISTERED: Mutex<Option<Source>> = Mutex::new(None);
+
+/// Every font file read so far. plotters keeps registered fonts for the rest of the process,
+/// so each file is read and leaked only once however often it is registered.
+static LOADED: OnceLock<Mutex<HashMap<PathBuf, &'static [u8]>>> = OnceLock::new();
+
+/// Lays out and draws chart text with `font`, or with the first system font found, or the
+/// bundled one, if none was registered yet. plotters measures text with it for SVG charts too.
+pub(crate) fn register(font: Option<&Path>) -> Result<(), ChartError> {
+    let mut registered = REGISTERED.lock().unwrap_or_else(|err| err.into_inner());
+    let source = match (font, registered.as_ref()) {
+        (Some(font), _) => Source::File(font.to_owned()),
+        (None, Some(_)) => return Ok(()),
+        (None, None) => SYSTEM_FONTS
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+            .map_or(Source::Bundled, Source::File),
+    };
+    if registered.as_ref() == Some(&source) {
+        return Ok(());
+    }
+
+    let data = match &source {
+        Source::File(path) => load(path)?,
+        Source::Bundled => BUNDLED_FONT,
+    };
+    plotters::style::register_font("sans-serif", FontStyle::Normal, data).map_err(|_| {
+        let path = match &source {
+            Source::File(path) => path.clone(),
+            Source::Bundled => PathBuf::from("FiraSans-Regular.ttf"),
+        };
+        ChartError::Font(path, "not a TrueType font".to_owned())
+    })?;
+    *registered = Some(source);
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<&'static [u8], ChartError> {
+    let mut loaded = LOADED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    if let Some(&data) = loaded.get(path) {
+        return Ok(data);
+    }
+    let data = fs::read(path).map_err(|err| ChartError::Font(path.to_owned(), err.to_string()))?;
+    let data: &'static [u8] = Box::leak(data.into_boxed_slice());
+    loaded.insert(path.to_owned(), data);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_font_loads() {
+        // Under its own family, so the charts of other tests keep theirs
+        assert!(plotters::style::register_font("bundled", FontStyle::Normal, BUNDLED_FONT).is_ok());
+    }
+}
@@ -0,0 +1,539 @@
+use plotters::{
+    coord::{ranged1d::SegmentValue, Shift},
+    prelude::*,
+};
+use shakespeare_monkey_solver::{
+    statistics::{SurvivalCurve, TrialSummary},
+    trajectory::TrajectoryPoint,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+mod font;
+
+/// Viridis, sampled at five evenly spaced stops.
+const COLOR_MAP: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+const LABEL_FONT: (&str, u32) = ("sans-serif", 14);
+const COLOR_BAR_WIDTH: u32 = 110;
+/// Heatmap axes label at most this many rows or columns.
+const MAX_LABELS: usize = 20;
+/// Drawn for heatmap cells whose statistic is undefined.
+const MISSING_COLOR: RGBColor = RGBColor(200, 200, 200);
+
+type DrawResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+
+#[derive(Debug)]
+pub enum ChartError {
+    NoTrials,
+    /// A convergence chart of trials that did not record their trajectories.
+    MissingTrajectories,
+    UnsupportedFormat(PathBuf),
+    Font(PathBuf, String),
+    Draw(String),
+}
+
+impl Display for ChartError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ChartError::NoTrials => write!(f, "no trials to chart"),
+            ChartError::MissingTrajectories => write!(
+                f,
+                "convergence charts need the trajectory of every trial, record them while running"
+            ),
+            ChartError::UnsupportedFormat(path) => write!(
+                f,
+                "charts can only be rendered to .svg or .png files, not {}",
+                path.display()
+            ),
+            ChartError::Font(path, err) => write!(f, "font {}: {err}", path.display()),
+            ChartError::Draw(err) => write!(f, "could not draw chart: {err}"),
+        }
+    }
+}
+
+impl Error for ChartError {}
+
+/// The trials of one configuration.
+#[derive(Clone, Debug, Default)]
+pub struct TrialSet {
+    pub mutation_rate: usize,
+    pub population_size: usize,
+    /// Length of the target term, which the best fitness is a fraction of.
+    pub num_genes: usize,
+    /// `(generations, censored)` of every trial.
+    pub samples: Vec<(usize, bool)>,
+    /// Trajectory of every trial, empty if none were recorded.
+    pub trajectories: Vec<Vec<TrajectoryPoint>>,
+}
+
+impl TrialSet {
+    pub fn new(mutation_rate: usize, population_size: usize, num_genes: usize) -> Self {
+        Self {
+            mutation_rate,
+            population_size,
+            num_genes,
+            ..Self::default()
+        }
+    }
+
+    fn label(&self) -> String {
+        format!(
+            "mutation rate {}, population size {}",
+            self.mutation_rate, self.population_size
+        )
+    }
+}
+
+/// Statistic of generations to solution shown by a heatmap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Statistic {
+    Mean,
+    Median,
+    /// Kaplan–Meier median, which accounts for censored trials but is undefined when fewer
+    /// than half the trials were solved.
+    SurvivalMedian,
+}
+
+impl Statistic {
+    fn of(&self, samples: &[(usize, bool)]) -> f64 {
+        let generations = samples
+            .iter()
+            .map(|&(generations, _)| generations)
+            .collect::<Vec<_>>();
+        match self {
+            Statistic::Mean => TrialSummary::from_samples(&generations).mean,
+            Statistic::Median => TrialSummary::from_samples(&generations).median,
+            Statistic::SurvivalMedian => SurvivalCurve::kaplan_meier(samples)
+                .median
+                .map_or(f64::NAN, |median| median as f64),
+        }
+    }
+}
+
+/// `(generations, censored)` of every trial of the sets sharing a mutation rate and
+/// population size, keyed by both.
+fn pool_by_cell(sets: &[TrialSet]) -> BTreeMap<(usize, usize), Vec<(usize, bool)>> {
+    let mut cells = BTreeMap::<_, Vec<_>>::new();
+    for set in sets {
+        cells
+            .entry((set.mutation_rate, set.population_size))
+            .or_default()
+            .extend(&set.samples);
+    }
+    cells
+}
+
+/// Best fitness of the trials of `set` at every generation any of them recorded. Trials that
+/// ended earlier, or were recorded less often, keep their last recorded fitness.
+fn convergence(set: &TrialSet) -> Vec<(usize, TrialSummary)> {
+    let generations = set
+        .trajectories
+        .iter()
+        .flatten()
+        .map(|point| point.generation)
+        .collect::<BTreeSet<_>>();
+    generations
+        .into_iter()
+        .map(|generation| {
+            let fitness = set
+                .trajectories
+                .iter()
+                .filter(|trajectory| !trajectory.is_empty())
+                .map(|trajectory| {
+                    let idx = trajectory.partition_point(|point| point.generation <= generation);
+                    trajectory[idx.saturating_sub(1)].best_fitness
+                })
+                .collect::<Vec<_>>();
+            (generation, TrialSummary::from_samples(&fitness))
+        })
+        .collect()
+}
+
+fn map_color(fraction: f64) -> RGBColor {
+    let position = fraction.clamp(0.0, 1.0) * (COLOR_MAP.len() - 1) as f64;
+    let idx = (position as usize).min(COLOR_MAP.len() - 2);
+    let weight = position - idx as f64;
+    let blend = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * weight).round() as u8;
+    let ((r0, g0, b0), (r1, g1, b1)) = (COLOR_MAP[idx], COLOR_MAP[idx + 1]);
+    RGBColor(blend(r0, r1), blend(g0, g1), blend(b0, b1))
+}
+
+pub enum Chart<'a> {
+    /// `statistic` of generations to solution over mutation rate × population size.
+    Heatmap(&'a [TrialSet], Statistic),
+    /// Mean best fitness over generations with its 95% confidence band, one line per set.
+    Convergence(&'a [TrialSet]),
+    /// Kaplan–Meier curve of every set.
+    Survival(&'a [TrialSet]),
+}
+
+impl Chart<'_> {
+    /// Draws the chart to an SVG or PNG file, depending on the extension of `path`. Text is
+    /// set in `font`, a TrueType file, or in a common system font if there is one and
+    /// the bundled Fira Sans otherwise.
+    pub fn render(
+        &self,
+        path: &Path,
+        size: (u32, u32),
+        title: &str,
+        font: Option<&Path>,
+    ) -> Result<(), ChartError> {
+        let (Chart::Heatmap(sets, _) | Chart::Convergence(sets) | Chart::Survival(sets)) = self;
+        if sets.iter().all(|set| set.samples.is_empty()) {
+            return Err(ChartError::NoTrials);
+        }
+        if let Chart::Convergence(sets) = self {
+            if sets.iter().any(|set| set.trajectories.is_empty()) {
+                return Err(ChartError::MissingTrajectories);
+            }
+        }
+
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        let result = match extension.as_deref() {
+            Some("svg") => {
+                font::register(font)?;
+                self.draw(SVGBackend::new(path, size).into_drawing_area(), title)
+                    .map_err(|err| err.to_string())
+            }
+            Some("png") => {
+                font::register(font)?;
+                self.draw(BitMapBackend::new(path, size).into_drawing_area(), title)
+                    .map_err(|err| err.to_string())
+            }
+            _ => return Err(ChartError::UnsupportedFormat(path.to_owned())),
+        };
+        result.map_err(ChartError::Draw)
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        title: &str,
+    ) -> DrawResult<DB> {
+        root.fill(&WHITE)?;
+        let area = root.titled(title, ("sans-serif", 24))?;
+        match self {
+            Chart::Heatmap(sets, statistic) => draw_heatmap(&area, sets, statistic)?,
+            Chart::Convergence(sets) => draw_convergence(&area, sets)?,
+            Chart::Survival(sets) => draw_survival(&area, sets)?,
+        }
+        root.present()
+    }
+}
+
+fn draw_heatmap<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sets: &[TrialSet],
+    statistic: &Statistic,
+) -> DrawResult<DB> {
+    let cells = pool_by_cell(sets)
+        .into_iter()
+        .map(|(cell, samples)| (cell, statistic.of(&samples)))
+        .collect::<BTreeMap<_, _>>();
+    let mut mutation_rates = cells.keys().map(|&(rate, _)| rate).collect::<Vec<_>>();
+    let mut population_sizes = cells.keys().map(|&(_, size)| size).collect::<Vec<_>>();
+    mutation_rates.dedup();
+    population_sizes.sort_unstable();
+    population_sizes.dedup();
+
+    let defined = || cells.values().copied().filter(|value| value.is_finite());
+    let (low, high) = (
+        defined().fold(f64::INFINITY, f64::min),
+        defined().fold(f64::NEG_INFINITY, f64::max),
+    );
+    let (low, high) = if low <= high {
+        (low, high.max(low + 1.0))
+    } else {
+        (0.0, 1.0)
+    };
+
+    let (main, bar) =
+        area.split_horizontally(area.dim_in_pixel().0.saturating_sub(COLOR_BAR_WIDTH));
+    let mut chart = ChartBuilder::on(&main)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(
+            (0..population_sizes.len() as i32 - 1).into_segmented(),
+            (0..mutation_rates.len() as i32 - 1).into_segmented(),
+        )?;
+    let label = |values: &[usize], value: &SegmentValue<i32>| match *value {
+        SegmentValue::CenterOf(idx) | SegmentValue::Exact(idx) => values
+            .get(idx as usize)
+            .map_or_else(String::new, usize::to_string),
+        SegmentValue::Last => String::new(),
+    };
+    chart
+        .configure_mesh()
+        .label_style(LABEL_FONT)
+        .axis_desc_style(LABEL_FONT)
+        .disable_mesh()
+        .x_desc("population size")
+        .y_desc("mutation rate")
+        .x_labels(population_sizes.len().min(MAX_LABELS))
+        .y_labels(mutation_rates.len().min(MAX_LABELS))
+        .x_label_formatter(&|value| label(&population_sizes, value))
+        .y_label_formatter(&|value| label(&mutation_rates, value))
+        .draw()?;
+    chart.draw_series(cells.iter().map(|(&(rate, size), &value)| {
+        let x = population_sizes.binary_search(&size).unwrap() as i32;
+        let y = mutation_rates.binary_search(&rate).unwrap() as i32;
+        let color = if value.is_finite() {
+            map_color((value - low) / (high - low))
+        } else {
+            MISSING_COLOR
+        };
+        Rectangle::new(
+            [
+                (SegmentValue::Exact(x), SegmentValue::Exact(y)),
+                (SegmentValue::Exact(x + 1), SegmentValue::Exact(y + 1)),
+            ],
+            color.filled(),
+        )
+    }))?;
+
+    let mut legend = ChartBuilder::on(&bar)
+        .margin(10)
+        .margin_left(0)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0.0..1.0, low..high)?;
+    legend
+        .configure_mesh()
+        .label_style(LABEL_FONT)
+        .axis_desc_style(LABEL_FONT)
+        .disable_mesh()
+        .disable_x_axis()
+        .y_desc("generations")
+        .y_label_formatter(&|generations| format!("{generations:.0}"))
+        .draw()?;
+    let steps = 100;
+    legend.draw_series((0..steps).map(|step| {
+        let (from, to) = (step as f64 / steps as f64, (step + 1) as f64 / steps as f64);
+        Rectangle::new(
+            [
+                (0.0, low + from * (high - low)),
+                (1.0, low + to * (high - low)),
+            ],
+            map_color(from).filled(),
+        )
+    }))?;
+    Ok(())
+}
+
+fn draw_convergence<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sets: &[TrialSet],
+) -> DrawResult<DB> {
+    // Fitness as a fraction of the target, so sets with different targets share the axis
+    let lines = sets
+        .iter()
+        .map(|set| {
+            let num_genes = set.num_genes.max(1) as f64;
+            let points = convergence(set)
+                .into_iter()
+                .map(|(generation, summary)| {
+                    let (low, high) = summary.confidence_interval;
+                    (
+                        generation,
+                        summary.mean / num_genes,
+                        (low / num_genes).max(0.0),
+                        (high / num_genes).min(1.0),
+                    )
+                })
+                .collect::<Vec<_>>();
+            (set.label(), points)
+        })
+        .collect::<Vec<_>>();
+    let x_high = lines
+        .iter()
+        .flat_map(|(_, points)| points.last())
+        .map(|&(generation, ..)| generation)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..x_high, 0.0..1.0)?;
+    chart
+        .configure_mesh()
+        .label_style(LABEL_FONT)
+        .axis_desc_style(LABEL_FONT)
+        .x_desc("generation")
+        .y_desc("best fitness")
+        .y_label_formatter(&|fraction| format!("{:.0}%", fraction * 100.0))
+        .draw()?;
+
+    for (idx, (label, points)) in lines.into_iter().enumerate() {
+        let color = Palette99::pick(idx).to_rgba();
+        let band = points
+            .iter()
+            .map(|&(generation, _, _, high)| (generation, high))
+            .chain(
+                points
+                    .iter()
+                    .rev()
+                    .map(|&(generation, _, low, _)| (generation, low)),
+            )
+            .collect::<Vec<_>>();
+        chart.draw_series(std::iter::once(Polygon::new(band, color.mix(0.2).filled())))?;
+        chart
+            .draw_series(LineSeries::new(
+                points
+                    .iter()
+                    .map(|&(generation, mean, ..)| (generation, mean)),
+                color.stroke_width(2),
+            ))?
+            .label(label)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .label_font(LABEL_FONT)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+}
+
+fn draw_survival<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    sets: &[TrialSet],
+) -> DrawResult<DB> {
+    let last_generation = |set: &TrialSet| {
+        set.samples
+            .iter()
+            .map(|&(generations, _)| generations)
+            .max()
+    };
+    let x_high = sets
+        .iter()
+        .flat_map(last_generation)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..x_high, 0.0..1.0)?;
+    chart
+        .configure_mesh()
+        .label_style(LABEL_FONT)
+        .axis_desc_style(LABEL_FONT)
+        .x_desc("generation")
+        .y_desc("fraction unsolved")
+        .draw()?;
+
+    for (idx, set) in sets.iter().enumerate() {
+        let color = Palette99::pick(idx).to_rgba();
+        let curve = SurvivalCurve::kaplan_meier(&set.samples);
+        // Steps down at every generation that solved a trial, and stays flat until the last
+        // trial, solved or censored
+        let mut steps = vec![(0, 1.0)];
+        let mut survival = 1.0;
+        for (&generation, &next) in curve.generations.iter().zip(&curve.survival) {
+            steps.extend([(generation, survival), (generation, next)]);
+            survival = next;
+        }
+        steps.push((last_generation(set).unwrap_or(0), survival));
+
+        chart
+            .draw_series(LineSeries::new(steps, color.stroke_width(2)))?
+            .label(set.label())
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .label_font(LABEL_FONT)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs};
+
+    /// Two grid points of three trials each, one trial censored.
+    fn trial_sets() -> Vec<TrialSet> {
+        [(1, 20), (2, 40)]
+            .into_iter()
+            .map(|(mutation_rate, population_size)| TrialSet {
+                samples: vec![(30, false), (45, false), (60, true)],
+                trajectories: [30, 45, 60]
+                    .into_iter()
+                    .map(|generations| {
+                        (0..=generations)
+                            .map(|generation| TrajectoryPoint {
+                                generation,
+                                best_fitness: generation * 10 / generations,
+                                mean_fitness: generation as f64 * 5.0 / generations as f64,
+                                diversity: 1.0 - generation as f64 / generations as f64,
+                            })
+                            .collect()
+                    })
+                    .collect(),
+                ..TrialSet::new(mutation_rate, population_size, 10)
+            })
+            .collect()
+    }
+
+    fn assert_renders(name: &str, chart: Chart) {
+        for extension in ["svg", "png"] {
+            let path = env::temp_dir().join(format!(
+                "monkey-charts-{}-{name}.{extension}",
+                std::process::id()
+            ));
+            chart.render(&path, (400, 300), name, None).unwrap();
+            let len = fs::metadata(&path).unwrap().len();
+            fs::remove_file(&path).unwrap();
+            assert!(len > 0, "{} is empty", path.display());
+        }
+    }
+
+    #[test]
+    fn heatmap_renders() {
+        let sets = trial_sets();
+        for statistic in [
+            Statistic::Mean,
+            Statistic::Median,
+            Statistic::SurvivalMedian,
+        ] {
+            assert_renders("heatmap", Chart::Heatmap(&sets, statistic));
+        }
+    }
+
+    #[test]
+    fn convergence_renders() {
+        assert_renders("convergence", Chart::Convergence(&trial_sets()));
+    }
+
+    #[test]
+    fn survival_renders() {
+        assert_renders("survival", Chart::Survival(&trial_sets()));
+    }
+}
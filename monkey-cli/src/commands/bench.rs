@@ -0,0 +1,58 @@
+use clap::Args;
+use shakespeare_monkey_solver::utils::derive_seed;
+use std::time::{Duration, Instant};
+
+use crate::{
+    config::{ConfigFile, PopulationOptions},
+    Result,
+};
+
+#[derive(Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    population: PopulationOptions,
+    /// Generations timed per repeat, whether or not the population solves the term
+    #[arg(long, default_value_t = 1000)]
+    generations: usize,
+    /// Independently seeded repeats, of which the fastest and median are reported
+    #[arg(long, default_value_t = 5)]
+    repeats: usize,
+}
+
+pub fn bench(args: BenchArgs, config: ConfigFile) -> Result<()> {
+    let settings = args.population.or(config.population).resolve()?;
+    if args.generations == 0 || args.repeats == 0 {
+        return Err("generations and repeats must be at least 1".into());
+    }
+
+    let mut times = (0..args.repeats)
+        .map(|repeat| {
+            let mut population =
+                settings.build_population_with_seed(derive_seed(settings.seed, repeat as u64));
+            let start = Instant::now();
+            for _ in 0..args.generations {
+                population.compute_biased_fitness();
+                population.update_generation();
+            }
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    times.sort_unstable();
+
+    let per_generation = |time: Duration| time.as_secs_f64() / args.generations as f64;
+    let fastest = per_generation(times[0]);
+    let median = per_generation(times[times.len() / 2]);
+    println!("seed                  {}", settings.seed);
+    println!(
+        "population            {} candidates x {} genes",
+        settings.population_size,
+        settings.target_term.chars().count()
+    );
+    println!("fastest               {:.3} ms/generation", fastest * 1e3);
+    println!("median                {:.3} ms/generation", median * 1e3);
+    println!(
+        "evaluations           {:.0}/s",
+        settings.population_size as f64 / median
+    );
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod bench;
+pub mod replay;
+pub mod run;
+pub mod segmented;
+pub mod sweep;
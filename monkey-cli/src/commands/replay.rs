@@ -0,0 +1,114 @@
+use clap::Args;
+use serde::Deserialize;
+use std::{fs::File, io::BufRead, io::BufReader, ops::ControlFlow, path::PathBuf};
+
+use crate::{config::Settings, output, Result};
+
+#[derive(Args)]
+pub struct ReplayArgs {
+    /// .csv or .jsonl file written by `run --output`
+    file: PathBuf,
+    /// Prints every candidate of this generation instead of the best of each generation
+    #[arg(long, short)]
+    generation: Option<usize>,
+}
+
+/// The columns of a `run` row that replaying checks.
+#[derive(Deserialize)]
+struct RecordedRow {
+    generation: usize,
+    best_fitness: usize,
+    best_genes: String,
+}
+
+fn read_rows(file: &PathBuf) -> Result<Vec<RecordedRow>> {
+    let reader = BufReader::new(File::open(file)?);
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => Ok(csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(reader)
+            .deserialize()
+            .collect::<std::result::Result<_, _>>()?),
+        _ => reader
+            .lines()
+            .skip(1)
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect(),
+    }
+}
+
+/// Re-simulates a recorded run from the settings in its header, with the same generation cap.
+/// Time caps are not reproducible, so runs with `max_seconds` stop at the generation their
+/// rows end at instead.
+pub fn replay(args: ReplayArgs) -> Result<()> {
+    let settings = output::read_settings(&args.file)?;
+    settings.validate()?;
+    let rows = read_rows(&args.file)?;
+    let last_generation = rows.last().map_or(0, |row| row.generation);
+    if let Some(generation) = args
+        .generation
+        .filter(|&generation| generation > last_generation)
+    {
+        return Err(format!(
+            "{} ends at generation {last_generation}, cannot replay generation {generation}",
+            args.file.display()
+        )
+        .into());
+    }
+
+    let settings = match settings.max_seconds {
+        Some(_) => Settings {
+            max_generations: Some(last_generation),
+            max_seconds: None,
+            ..settings
+        },
+        None => settings,
+    };
+    let mut population = settings.build_population();
+    population.record_events();
+    let mut rows = rows.iter();
+    let mut mismatch = None;
+    population.run_with(&settings.limits()?, |population, stats| {
+        let best = population.population[stats.best_candidate].to_string();
+        if let Some(row) = rows.next() {
+            if (row.generation, row.best_fitness, &row.best_genes)
+                != (stats.generation, stats.best_fitness, &best)
+            {
+                mismatch = Some(stats.generation);
+                return ControlFlow::Break(());
+            }
+        }
+        if args.generation.is_none() {
+            println!(
+                "{:>7}  {:>3}/{}  {best}",
+                stats.generation,
+                stats.best_fitness,
+                population.num_genes()
+            );
+        }
+        ControlFlow::Continue(())
+    });
+    if let Some(generation) = mismatch {
+        return Err(format!(
+            "replay diverges from {} at generation {generation}, it was edited or recorded with \
+             different solver code",
+            args.file.display()
+        )
+        .into());
+    }
+
+    if let Some(generation) = args.generation {
+        let event_log = population.event_log.as_ref().expect("events were recorded");
+        let mut candidates = event_log
+            .replay(generation)
+            .expect("generation was simulated");
+        for (idx, candidate) in candidates.iter_mut().enumerate() {
+            let fitness = candidate.compute_fitness(population.target_genes());
+            println!(
+                "{idx:>5}  {fitness:>3}/{}  {candidate}",
+                population.num_genes()
+            );
+        }
+    }
+    Ok(())
+}
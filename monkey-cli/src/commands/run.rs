@@ -0,0 +1,177 @@
+use clap::Args;
+use shakespeare_monkey_solver::{
+    generation_log::GenerationLog, population::Population, run::RunResult, stats::GenerationStats,
+};
+use std::{
+    io::{self, IsTerminal, Write},
+    ops::ControlFlow,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::{corpus_target, ConfigFile, CorpusOptions, PopulationOptions, Settings},
+    output::{self, create},
+    tui::Tui,
+    Result,
+};
+
+/// How often the progress line is redrawn.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    population: PopulationOptions,
+    #[command(flatten)]
+    corpus: CorpusOptions,
+    /// Position of the target term in the corpus [default: random]
+    #[arg(long, requires = "source")]
+    corpus_index: Option<usize>,
+    /// Writes one row per generation to this .csv or .jsonl file
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Writes every candidate to this .csv or .jsonl file, in the format of `--output`
+    #[arg(long, requires = "output")]
+    population_output: Option<PathBuf>,
+    /// Generations between the populations written to `--population-output`
+    #[arg(long, default_value_t = 100)]
+    population_interval: usize,
+    /// Prints no progress while running
+    #[arg(long, short)]
+    quiet: bool,
+    /// Follows the run in a terminal UI that can pause, step and slow it down
+    #[arg(long, conflicts_with = "quiet")]
+    tui: bool,
+}
+
+/// Status line on stderr, redrawn in place on a terminal and printed once a second otherwise.
+struct Progress {
+    is_terminal: bool,
+    interval: Duration,
+    last_update: Option<Instant>,
+}
+
+impl Progress {
+    fn new() -> Self {
+        let is_terminal = io::stderr().is_terminal();
+        Self {
+            is_terminal,
+            interval: if is_terminal {
+                PROGRESS_INTERVAL
+            } else {
+                Duration::from_secs(1)
+            },
+            last_update: None,
+        }
+    }
+
+    fn update(&mut self, population: &Population, stats: &GenerationStats, elapsed: Duration) {
+        if self
+            .last_update
+            .is_some_and(|last_update| last_update.elapsed() < self.interval)
+            && !stats.solved
+        {
+            return;
+        }
+        self.last_update = Some(Instant::now());
+
+        let line = format!(
+            "generation {:>7}  best {:>3}/{}  mean {:>7.2}  {:>8.0} gen/s  {}",
+            stats.generation,
+            stats.best_fitness,
+            population.num_genes(),
+            stats.mean_fitness,
+            stats.generation as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            population.population[stats.best_candidate],
+        );
+        let mut stderr = io::stderr().lock();
+        let _ = if self.is_terminal {
+            write!(stderr, "\r\x1b[2K{line}")
+        } else {
+            writeln!(stderr, "{line}")
+        };
+    }
+
+    fn finish(&self) {
+        if self.is_terminal && self.last_update.is_some() {
+            eprintln!();
+        }
+    }
+}
+
+pub fn run(args: RunArgs, config: ConfigFile) -> Result<()> {
+    let options = args.population.or(config.population);
+    let corpus = args.corpus.or(config.corpus).load(&options)?;
+    let mut settings = options.resolve()?;
+    if let Some(corpus) = corpus {
+        settings.target_term = corpus_target(&corpus, args.corpus_index, settings.seed)?;
+        settings.validate()?;
+    }
+    let mut population = settings.build_population();
+    let mut log = match args.output.as_deref() {
+        Some(path) => {
+            let format = output::format_of(path)?;
+            let mut log = GenerationLog::new(create(path)?, format, &settings)?;
+            if let Some(path) = args.population_output.as_deref() {
+                log = log.log_population(create(path)?, args.population_interval)?;
+            }
+            Some(log)
+        }
+        None => None,
+    };
+    let mut tui = args.tui.then(Tui::new).transpose()?;
+    let mut progress = (!args.quiet && tui.is_none()).then(Progress::new);
+
+    let start = Instant::now();
+    let mut error = None;
+    let result = population.run_with(&settings.limits()?, |population, stats| {
+        if let Some(progress) = progress.as_mut() {
+            progress.update(population, stats, start.elapsed());
+        }
+        if let Some(log) = log.as_mut() {
+            if let Err(err) = log.record(population, stats) {
+                error = Some(err.into());
+                return ControlFlow::Break(());
+            }
+        }
+        match tui.as_mut().map(|tui| tui.on_generation(population, stats)) {
+            Some(Ok(flow)) => flow,
+            Some(Err(err)) => {
+                error = Some(err.into());
+                ControlFlow::Break(())
+            }
+            None => ControlFlow::Continue(()),
+        }
+    });
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+    if let Some(tui) = tui.filter(|_| error.is_none()) {
+        tui.finish(&population, &result)?;
+    }
+    if let Some(err) = error {
+        return Err(err);
+    }
+    if let Some(log) = log {
+        log.finish()?;
+    }
+
+    print_summary(&settings, &population, &result);
+    Ok(())
+}
+
+fn print_summary(settings: &Settings, population: &Population, result: &RunResult) {
+    let stats = population.stats();
+    println!("outcome      {:?}", result.outcome);
+    println!("seed         {}", settings.seed);
+    println!("generations  {}", result.generations);
+    println!("evaluations  {}", result.evaluations);
+    println!("elapsed      {:.3}s", result.elapsed.as_secs_f64());
+    println!(
+        "best         {} ({}/{})",
+        population.population[stats.best_candidate],
+        stats.best_fitness,
+        population.num_genes()
+    );
+}
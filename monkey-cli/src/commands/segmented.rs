@@ -0,0 +1,117 @@
+use clap::{Args, ValueEnum};
+use shakespeare_monkey_solver::segmented::{SegmentResult, SegmentStrategy, SegmentedSolver};
+
+use crate::{
+    config::{corpus_target, ConfigFile, CorpusOptions, PopulationOptions, Settings},
+    Result,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum StrategyKind {
+    /// Evolves every segment on its own, on all cores
+    Parallel,
+    /// Evolves one segment after another, each after the frozen best of the ones before
+    Curriculum,
+}
+
+#[derive(Args)]
+pub struct SegmentedArgs {
+    #[command(flatten)]
+    population: PopulationOptions,
+    #[command(flatten)]
+    corpus: CorpusOptions,
+    /// Position of the target term in the corpus [default: random]
+    #[arg(long, requires = "source")]
+    corpus_index: Option<usize>,
+    /// Characters per segment
+    #[arg(long, default_value_t = 20)]
+    segment_length: usize,
+    #[arg(long, value_enum, default_value_t = StrategyKind::Parallel)]
+    strategy: StrategyKind,
+    /// Prints no progress while running
+    #[arg(long, short)]
+    quiet: bool,
+}
+
+/// Solves a long target term in segments, limits applying to each segment.
+pub fn segmented(args: SegmentedArgs, config: ConfigFile) -> Result<()> {
+    if args.segment_length == 0 {
+        return Err("segment length must be at least 1".into());
+    }
+    let options = args.population.or(config.population);
+    let corpus = args.corpus.or(config.corpus).load(&options)?;
+    let mut settings = options.resolve()?;
+    if let Some(corpus) = corpus {
+        settings.target_term = corpus_target(&corpus, args.corpus_index, settings.seed)?;
+        settings.validate()?;
+    }
+    let solver = SegmentedSolver {
+        segment_length: args.segment_length,
+        strategy: match args.strategy {
+            StrategyKind::Parallel => SegmentStrategy::Parallel,
+            StrategyKind::Curriculum => SegmentStrategy::Curriculum,
+        },
+        limits: settings.limits()?,
+    };
+    let num_segments = solver.segments(&settings.target_term).len();
+
+    let result = solver.solve_with(
+        &settings.target_term,
+        settings.seed,
+        |target_term, seed| {
+            Settings {
+                target_term,
+                ..settings.clone()
+            }
+            .build_population_with_seed(seed)
+        },
+        |segment| {
+            if !args.quiet {
+                eprintln!(
+                    "segment {}/{num_segments} {:?} after {} generations",
+                    segment.index + 1,
+                    segment.result.outcome,
+                    segment.result.generations
+                );
+            }
+        },
+    )?;
+
+    println!(
+        "{:>7} {:>11} {:>11} {:>9} {:>9}  best",
+        "segment", "generations", "evaluations", "elapsed", "fitness"
+    );
+    for segment in &result.segments {
+        print_segment(segment);
+    }
+    println!();
+    println!("strategy     {:?}", args.strategy);
+    println!("seed         {}", settings.seed);
+    println!("solved       {}", result.solved);
+    println!("generations  {}", result.generations());
+    println!("evaluations  {}", result.evaluations());
+    println!("elapsed      {:.3}s", result.elapsed.as_secs_f64());
+    println!(
+        "best         {} ({}/{})",
+        result.stitched,
+        result.fitness,
+        settings.target_term.chars().count()
+    );
+    Ok(())
+}
+
+fn print_segment(segment: &SegmentResult) {
+    println!(
+        "{:>7} {:>11} {:>11} {:>8.3}s {:>9}  {}",
+        segment.index,
+        segment.result.generations,
+        segment.result.evaluations,
+        segment.result.elapsed.as_secs_f64(),
+        format!(
+            "{}/{}",
+            segment.best_fitness,
+            segment.target_term.chars().count()
+        ),
+        segment.best_genes
+    );
+}
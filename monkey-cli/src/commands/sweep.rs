@@ -0,0 +1,256 @@
+use clap::Args;
+use monkey_charts::{Chart, Statistic, TrialSet};
+use rayon::prelude::*;
+use serde::Serialize;
+use shakespeare_monkey_solver::{
+    trajectory::{Trajectory, TrajectoryPoint},
+    utils::{derive_seed, random::Random},
+};
+use std::{
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::{ConfigFile, CorpusOptions, PopulationOptions, Settings, SweepOptions},
+    output, Result,
+};
+
+#[derive(Args)]
+pub struct SweepArgs {
+    #[command(flatten)]
+    population: PopulationOptions,
+    #[command(flatten)]
+    sweep: SweepOptions,
+    #[command(flatten)]
+    corpus: CorpusOptions,
+    /// Target terms drawn at random from the corpus [default: every term]
+    #[arg(long, requires = "source")]
+    corpus_sample: Option<usize>,
+    /// Writes one row per trial to this .csv or .jsonl file
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Renders heatmap, convergence and survival charts of the sweep into this directory
+    #[arg(long)]
+    report: Option<PathBuf>,
+    /// TrueType font for the report's text [default: a common system font, else the bundled Fira Sans]
+    #[arg(long, requires = "report")]
+    font: Option<PathBuf>,
+}
+
+/// Generations kept of every trial's trajectory for the convergence chart.
+const REPORT_TRAJECTORY_POINTS: usize = 500;
+const REPORT_SIZE: (u32, u32) = (1000, 700);
+
+/// One trial of a grid point. Every point shares the trial seeds, so points are compared on
+/// the same random numbers.
+#[derive(Serialize)]
+struct TrialRow {
+    target_term: String,
+    mutation_rate: usize,
+    population_size: usize,
+    trial: usize,
+    seed: u64,
+    outcome: String,
+    censored: bool,
+    generations: usize,
+    evaluations: usize,
+    best_fitness: usize,
+    elapsed_seconds: f64,
+}
+
+pub fn sweep(args: SweepArgs, config: ConfigFile) -> Result<()> {
+    let options = args.population.or(config.population);
+    let corpus = args.corpus.or(config.corpus).load(&options)?;
+    let settings = options.resolve()?;
+    let sweep = args.sweep.or(config.sweep);
+    let target_terms = match corpus {
+        Some(corpus) => {
+            let mut target_terms = corpus.targets().to_vec();
+            if args.corpus_sample == Some(0) {
+                return Err("corpus sample must be at least 1".into());
+            }
+            if let Some(sample) = args.corpus_sample {
+                // Partial Fisher-Yates shuffle, seeded so the sample is part of the sweep's seed
+                let mut rng = Random::from_seed(settings.seed);
+                let sample = sample.min(target_terms.len());
+                for idx in 0..sample {
+                    let pick = rng.gen_range_usize(idx..target_terms.len());
+                    target_terms.swap(idx, pick);
+                }
+                target_terms.truncate(sample);
+            }
+            target_terms
+        }
+        None => vec![settings.target_term.clone()],
+    };
+    let mutation_rates = if sweep.mutation_rates.is_empty() {
+        vec![settings.mutation_rate]
+    } else {
+        sweep.mutation_rates
+    };
+    let population_sizes = if sweep.population_sizes.is_empty() {
+        vec![settings.population_size]
+    } else {
+        sweep.population_sizes
+    };
+    if population_sizes.contains(&0) {
+        return Err("population sizes must be at least 1".into());
+    }
+    let trials = sweep.trials.unwrap_or(1);
+    if trials == 0 {
+        return Err("trials must be at least 1".into());
+    }
+    let limits = settings.limits()?;
+
+    let mut points = Vec::new();
+    for target_term in &target_terms {
+        for &mutation_rate in &mutation_rates {
+            for &population_size in &population_sizes {
+                let point = Settings {
+                    target_term: target_term.clone(),
+                    mutation_rate,
+                    population_size,
+                    ..settings.clone()
+                };
+                point.validate()?;
+                points.push(point);
+            }
+        }
+    }
+    let runs = points
+        .iter()
+        .flat_map(|point| (0..trials).map(move |trial| (point, trial)))
+        .collect::<Vec<_>>();
+    let (rows, trajectories): (Vec<_>, Vec<_>) = runs
+        .par_iter()
+        .map(|&(point, trial)| {
+            let seed = derive_seed(point.seed, trial as u64);
+            let mut population = point.build_population_with_seed(seed);
+            let mut trajectory = args
+                .report
+                .is_some()
+                .then(|| Trajectory::new(Some(REPORT_TRAJECTORY_POINTS)));
+            let result = population.run_with(&limits, |population, stats| {
+                if let Some(trajectory) = trajectory.as_mut() {
+                    trajectory.record(population, stats);
+                }
+                ControlFlow::Continue(())
+            });
+            if let Some(trajectory) = trajectory.as_mut() {
+                trajectory.finish(&population);
+            }
+            let row = TrialRow {
+                target_term: point.target_term.clone(),
+                mutation_rate: point.mutation_rate,
+                population_size: point.population_size,
+                trial,
+                seed,
+                outcome: format!("{:?}", result.outcome),
+                censored: result.is_censored(),
+                generations: result.generations,
+                evaluations: result.evaluations,
+                best_fitness: population.stats().best_fitness,
+                elapsed_seconds: result.elapsed.as_secs_f64(),
+            };
+            (row, trajectory.map(Trajectory::into_points))
+        })
+        .unzip();
+
+    if let Some(path) = args.output.as_deref() {
+        let mut output = output::create_rows(path, &settings)?;
+        for row in &rows {
+            output.write(row)?;
+        }
+        output.finish()?;
+    }
+    if let Some(dir) = args.report.as_deref() {
+        write_report(dir, args.font.as_deref(), trials, &rows, trajectories)?;
+    }
+
+    println!(
+        "{:>13} {:>15} {:>7} {:>18} {:>18} {:>11}",
+        "mutation_rate",
+        "population_size",
+        "solved",
+        "median_generations",
+        "mean_evaluations",
+        "mean_time"
+    );
+    for point_rows in rows.chunks(trials) {
+        let solved = point_rows.iter().filter(|row| !row.censored).count();
+        let mut generations = point_rows
+            .iter()
+            .map(|row| row.generations)
+            .collect::<Vec<_>>();
+        generations.sort_unstable();
+        let median = match generations.len() % 2 {
+            1 => generations[generations.len() / 2] as f64,
+            _ => {
+                let upper = generations.len() / 2;
+                (generations[upper - 1] + generations[upper]) as f64 / 2.0
+            }
+        };
+        let mean = |value: fn(&TrialRow) -> f64| {
+            point_rows.iter().map(value).sum::<f64>() / point_rows.len() as f64
+        };
+        println!(
+            "{:>13} {:>15} {:>7} {:>18} {:>18.0} {:>10.3}s  {}",
+            point_rows[0].mutation_rate,
+            point_rows[0].population_size,
+            format!("{solved}/{trials}"),
+            median,
+            mean(|row| row.evaluations as f64),
+            mean(|row| row.elapsed_seconds),
+            point_rows[0].target_term,
+        );
+    }
+    Ok(())
+}
+
+/// Charts every grid point of the sweep as one trial set.
+fn write_report(
+    dir: &Path,
+    font: Option<&Path>,
+    trials: usize,
+    rows: &[TrialRow],
+    trajectories: Vec<Option<Vec<TrajectoryPoint>>>,
+) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut trajectories = trajectories.into_iter().flatten();
+    let sets = rows
+        .chunks(trials)
+        .map(|point_rows| {
+            let row = &point_rows[0];
+            TrialSet {
+                samples: point_rows
+                    .iter()
+                    .map(|row| (row.generations, row.censored))
+                    .collect(),
+                trajectories: trajectories.by_ref().take(trials).collect(),
+                ..TrialSet::new(
+                    row.mutation_rate,
+                    row.population_size,
+                    row.target_term.chars().count(),
+                )
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let charts = [
+        (
+            "heatmap.svg",
+            "median generations to solution",
+            Chart::Heatmap(&sets, Statistic::Median),
+        ),
+        ("convergence.svg", "best fitness", Chart::Convergence(&sets)),
+        ("survival.svg", "unsolved trials", Chart::Survival(&sets)),
+    ];
+    for (name, title, chart) in charts {
+        let path = dir.join(name);
+        chart.render(&path, REPORT_SIZE, title, font)?;
+        eprintln!("wrote {}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,511 @@
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use shakespeare_monkey_solver::{
+    biased_scale::BiasedScale,
+    corpus::{Corpus, Split},
+    dna::Crossover,
+    population::{FitnessEvaluation, Population, Selection},
+    run::RunLimits,
+    utils::{random::Random, sampler::Sampler},
+};
+use std::{fs, path::Path, time::Duration};
+
+use crate::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScaleKind {
+    Multiplicative,
+    Order,
+    Exponential,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionKind {
+    Proportional,
+    Tournament,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum CrossoverKind {
+    SinglePoint,
+    TwoPoint,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SamplerKind {
+    Auto,
+    Cumulative,
+    Alias,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum EvaluationKind {
+    Full,
+    Incremental,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum SplitKind {
+    Lines,
+    Sentences,
+    Chunks,
+}
+
+/// Population settings as given on the command line or in the `[population]` table of a
+/// config file. Unset options fall back to the config file, then to the defaults.
+#[derive(Clone, Debug, Default, Args, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PopulationOptions {
+    /// Term the population evolves towards [default: "Hello World"]
+    #[arg(long)]
+    pub target_term: Option<String>,
+    /// Chance in percent that each gene of a child mutates [default: 4]
+    #[arg(long)]
+    pub mutation_rate: Option<usize>,
+    /// Candidates per generation [default: 50]
+    #[arg(long)]
+    pub population_size: Option<usize>,
+    /// Biased scale applied to fitness before proportional selection [default: multiplicative]
+    #[arg(long, value_enum)]
+    pub scale: Option<ScaleKind>,
+    /// Factor of the biased scale [default: 1.4]
+    #[arg(long)]
+    pub scale_factor: Option<f64>,
+    /// How parents are drawn [default: proportional]
+    #[arg(long, value_enum)]
+    pub selection: Option<SelectionKind>,
+    /// Candidates drawn per tournament with `--selection tournament` [default: 2]
+    #[arg(long)]
+    pub tournament_size: Option<usize>,
+    /// Segment children take from their second parent [default: single-point]
+    #[arg(long, value_enum)]
+    pub crossover: Option<CrossoverKind>,
+    /// Fittest candidates copied unchanged into every next generation [default: 0]
+    #[arg(long)]
+    pub elitism: Option<usize>,
+    /// Sampler of proportional selection [default: auto]
+    #[arg(long, value_enum)]
+    pub sampler: Option<SamplerKind>,
+    /// Rescan every child, or derive its fitness from its parents [default: incremental]
+    #[arg(long, value_enum)]
+    pub fitness_evaluation: Option<EvaluationKind>,
+    /// Seed of the random number generator [default: random]
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Stop after this many generations
+    #[arg(long)]
+    pub max_generations: Option<usize>,
+    /// Stop after this many seconds of wall-clock time
+    #[arg(long)]
+    pub max_seconds: Option<f64>,
+}
+
+/// Grid of a sweep as given on the command line or in the `[sweep]` table of a config file.
+#[derive(Clone, Debug, Default, Args, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SweepOptions {
+    /// Mutation rates to sweep, comma separated [default: the population's]
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub mutation_rates: Vec<usize>,
+    /// Population sizes to sweep, comma separated [default: the population's]
+    #[arg(long, value_delimiter = ',')]
+    #[serde(default)]
+    pub population_sizes: Vec<usize>,
+    /// Independently seeded runs per grid point [default: 1]
+    #[arg(long)]
+    pub trials: Option<usize>,
+}
+
+/// Text to take target terms from, as given on the command line or in the `[corpus]` table
+/// of a config file.
+#[derive(Clone, Debug, Default, Args, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorpusOptions {
+    /// Text file to take target terms from, or `shakespeare` for the bundled sample
+    #[arg(long = "corpus", conflicts_with = "target_term")]
+    pub source: Option<String>,
+    /// How the corpus is cut into target terms [default: lines]
+    #[arg(long, value_enum)]
+    pub split: Option<SplitKind>,
+    /// Characters per target term with `--split chunks` [default: 40]
+    #[arg(long)]
+    pub chunk_size: Option<usize>,
+    /// Keeps characters no gene can match, such as `;` and `?`, making their terms unsolvable
+    #[arg(long)]
+    #[serde(default)]
+    pub keep_unencodable: bool,
+}
+
+/// A TOML or JSON config file, told apart by its extension.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub population: PopulationOptions,
+    #[serde(default)]
+    pub sweep: SweepOptions,
+    #[serde(default)]
+    pub corpus: CorpusOptions,
+}
+
+impl ConfigFile {
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::default());
+        };
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let config = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&contents).map_err(|err| err.to_string()),
+            _ => toml::from_str(&contents).map_err(|err| err.to_string()),
+        };
+        Ok(config.map_err(|err| format!("invalid config file {}: {err}", path.display()))?)
+    }
+}
+
+impl PopulationOptions {
+    /// Fills every option unset here from `fallback`.
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            target_term: self.target_term.or(fallback.target_term),
+            mutation_rate: self.mutation_rate.or(fallback.mutation_rate),
+            population_size: self.population_size.or(fallback.population_size),
+            scale: self.scale.or(fallback.scale),
+            scale_factor: self.scale_factor.or(fallback.scale_factor),
+            selection: self.selection.or(fallback.selection),
+            tournament_size: self.tournament_size.or(fallback.tournament_size),
+            crossover: self.crossover.or(fallback.crossover),
+            elitism: self.elitism.or(fallback.elitism),
+            sampler: self.sampler.or(fallback.sampler),
+            fitness_evaluation: self.fitness_evaluation.or(fallback.fitness_evaluation),
+            seed: self.seed.or(fallback.seed),
+            max_generations: self.max_generations.or(fallback.max_generations),
+            max_seconds: self.max_seconds.or(fallback.max_seconds),
+        }
+    }
+
+    /// Applies the defaults, drawing a seed if none was given.
+    pub fn resolve(self) -> Result<Settings> {
+        let settings = Settings {
+            target_term: self.target_term.unwrap_or_else(|| "Hello World".to_owned()),
+            mutation_rate: self.mutation_rate.unwrap_or(4),
+            population_size: self.population_size.unwrap_or(50),
+            scale: self.scale.unwrap_or(ScaleKind::Multiplicative),
+            scale_factor: self.scale_factor.unwrap_or(1.4),
+            selection: self.selection.unwrap_or(SelectionKind::Proportional),
+            tournament_size: self.tournament_size.unwrap_or_else(default_tournament_size),
+            crossover: self.crossover.unwrap_or(CrossoverKind::SinglePoint),
+            elitism: self.elitism.unwrap_or(0),
+            sampler: self.sampler.unwrap_or(SamplerKind::Auto),
+            fitness_evaluation: self
+                .fitness_evaluation
+                .unwrap_or(EvaluationKind::Incremental),
+            seed: self.seed.unwrap_or_else(Random::entropy_seed),
+            max_generations: self.max_generations,
+            max_seconds: self.max_seconds,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+impl SweepOptions {
+    pub fn or(self, fallback: Self) -> Self {
+        let or_list = |list: Vec<usize>, fallback: Vec<usize>| {
+            if list.is_empty() {
+                fallback
+            } else {
+                list
+            }
+        };
+        Self {
+            mutation_rates: or_list(self.mutation_rates, fallback.mutation_rates),
+            population_sizes: or_list(self.population_sizes, fallback.population_sizes),
+            trials: self.trials.or(fallback.trials),
+        }
+    }
+}
+
+impl CorpusOptions {
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            source: self.source.or(fallback.source),
+            split: self.split.or(fallback.split),
+            chunk_size: self.chunk_size.or(fallback.chunk_size),
+            keep_unencodable: self.keep_unencodable || fallback.keep_unencodable,
+        }
+    }
+
+    /// The corpus to take target terms from, if one was given. Fails if `population` sets a
+    /// target term too, or if the corpus holds no terms.
+    pub fn load(&self, population: &PopulationOptions) -> Result<Option<Corpus>> {
+        let Some(source) = self.source.as_deref() else {
+            return Ok(None);
+        };
+        if population.target_term.is_some() {
+            return Err("a target term and a corpus cannot both be given".into());
+        }
+        let split = match self.split.unwrap_or(SplitKind::Lines) {
+            SplitKind::Lines => Split::Lines,
+            SplitKind::Sentences => Split::Sentences,
+            SplitKind::Chunks => Split::Chunks(self.chunk_size.unwrap_or(40)),
+        };
+        let mut corpus = match source {
+            "shakespeare" => Corpus::shakespeare(split),
+            path => Corpus::load(path, split)
+                .map_err(|err| format!("failed to read corpus {path}: {err}"))?,
+        };
+        if !self.keep_unencodable {
+            corpus = corpus.retain_encodable();
+        }
+        if corpus.is_empty() {
+            return Err(format!("corpus {source} holds no target terms").into());
+        }
+        Ok(Some(corpus))
+    }
+}
+
+/// The target term at `index` of `corpus`, or one picked at random with `seed`.
+pub fn corpus_target(corpus: &Corpus, index: Option<usize>, seed: u64) -> Result<String> {
+    let target_term = match index {
+        Some(idx) => corpus.get(idx).ok_or_else(|| {
+            format!(
+                "corpus index {idx} is out of range, the corpus holds {} target terms",
+                corpus.len()
+            )
+        })?,
+        None => corpus
+            .pick(&mut Random::from_seed(seed))
+            .expect("loaded corpora are not empty"),
+    };
+    Ok(target_term.to_owned())
+}
+
+/// Every setting of one run, recorded in the header of its output so `replay` can repeat it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub target_term: String,
+    pub mutation_rate: usize,
+    pub population_size: usize,
+    pub scale: ScaleKind,
+    pub scale_factor: f64,
+    pub selection: SelectionKind,
+    pub tournament_size: usize,
+    pub crossover: CrossoverKind,
+    pub elitism: usize,
+    pub sampler: SamplerKind,
+    pub fitness_evaluation: EvaluationKind,
+    pub seed: u64,
+    pub max_generations: Option<usize>,
+    pub max_seconds: Option<f64>,
+}
+
+fn default_tournament_size() -> usize {
+    2
+}
+
+impl Settings {
+    /// Needed again whenever the target term or population size changes after `resolve`.
+    pub fn validate(&self) -> Result<()> {
+        if self.population_size == 0 {
+            return Err("population size must be at least 1".into());
+        }
+        if self.target_term.is_empty() {
+            return Err("target term must not be empty".into());
+        }
+        if self.tournament_size == 0 {
+            return Err("tournament size must be at least 1".into());
+        }
+        // Tournament selection never weighs candidates by their biased fitness
+        if self.selection == SelectionKind::Proportional {
+            self.biased_scale()
+                .validate(self.target_term.chars().count(), self.population_size)?;
+        }
+        self.limits()?;
+        Ok(())
+    }
+
+    pub fn limits(&self) -> Result<RunLimits> {
+        Ok(RunLimits {
+            max_generations: self.max_generations,
+            max_duration: self
+                .max_seconds
+                .map(Duration::try_from_secs_f64)
+                .transpose()
+                .map_err(|_| "max seconds must be a non-negative number")?,
+        })
+    }
+
+    pub fn build_population(&self) -> Population {
+        self.build_population_with_seed(self.seed)
+    }
+
+    pub fn biased_scale(&self) -> BiasedScale {
+        match self.scale {
+            ScaleKind::Multiplicative => BiasedScale::Multiplicative(self.scale_factor),
+            ScaleKind::Order => BiasedScale::Order(self.scale_factor),
+            ScaleKind::Exponential => BiasedScale::Exponential(self.scale_factor),
+        }
+    }
+
+    pub fn build_population_with_seed(&self, seed: u64) -> Population {
+        let mut population = Population::with_seed(
+            self.target_term.clone(),
+            self.mutation_rate,
+            self.population_size,
+            self.biased_scale(),
+            seed,
+        );
+        population.selection = match self.selection {
+            SelectionKind::Proportional => Selection::Proportional,
+            SelectionKind::Tournament => Selection::Tournament(self.tournament_size),
+        };
+        population.crossover = match self.crossover {
+            CrossoverKind::SinglePoint => Crossover::SinglePoint,
+            CrossoverKind::TwoPoint => Crossover::TwoPoint,
+        };
+        population.sampler = match self.sampler {
+            SamplerKind::Auto => Sampler::Auto,
+            SamplerKind::Cumulative => Sampler::Cumulative,
+            SamplerKind::Alias => Sampler::Alias,
+        };
+        population.fitness_evaluation = match self.fitness_evaluation {
+            EvaluationKind::Full => FitnessEvaluation::Full,
+            EvaluationKind::Incremental => FitnessEvaluation::Incremental,
+        };
+        population.elitism = self.elitism;
+        population
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(target_term: &str) -> PopulationOptions {
+        PopulationOptions {
+            target_term: Some(target_term.to_owned()),
+            seed: Some(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn command_line_overrides_config_file() {
+        let config: ConfigFile = toml::from_str(
+            "[population]\n\
+             target_term = \"From the file\"\n\
+             mutation_rate = 7\n\
+             scale = \"order\"\n\
+             [sweep]\n\
+             population_sizes = [10, 20]\n",
+        )
+        .unwrap();
+        let command_line = PopulationOptions {
+            mutation_rate: Some(2),
+            ..options("From the command line")
+        };
+
+        let settings = command_line.or(config.population).resolve().unwrap();
+        assert_eq!(settings.target_term, "From the command line");
+        assert_eq!(settings.mutation_rate, 2);
+        assert_eq!(settings.scale, ScaleKind::Order);
+
+        let sweep = SweepOptions::default().or(config.sweep);
+        assert_eq!(sweep.population_sizes, [10, 20]);
+        assert!(sweep.mutation_rates.is_empty());
+    }
+
+    #[test]
+    fn resolve_applies_the_defaults() {
+        let settings = PopulationOptions {
+            seed: Some(9),
+            ..Default::default()
+        }
+        .resolve()
+        .unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                target_term: "Hello World".to_owned(),
+                mutation_rate: 4,
+                population_size: 50,
+                scale: ScaleKind::Multiplicative,
+                scale_factor: 1.4,
+                selection: SelectionKind::Proportional,
+                tournament_size: 2,
+                crossover: CrossoverKind::SinglePoint,
+                elitism: 0,
+                sampler: SamplerKind::Auto,
+                fitness_evaluation: EvaluationKind::Incremental,
+                seed: 9,
+                max_generations: None,
+                max_seconds: None,
+            }
+        );
+        let header = serde_json::to_string(&settings).unwrap();
+        assert_eq!(serde_json::from_str::<Settings>(&header).unwrap(), settings);
+    }
+
+    #[test]
+    fn unknown_config_keys_are_rejected() {
+        assert!(toml::from_str::<ConfigFile>("[population]\nmutation = 3\n").is_err());
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_settings() {
+        let invalid = [
+            PopulationOptions {
+                population_size: Some(0),
+                ..options("Hello")
+            },
+            options(""),
+            PopulationOptions {
+                selection: Some(SelectionKind::Tournament),
+                tournament_size: Some(0),
+                ..options("Hello")
+            },
+            PopulationOptions {
+                scale: Some(ScaleKind::Order),
+                scale_factor: Some(-1.0),
+                ..options("Hello")
+            },
+            PopulationOptions {
+                scale_factor: Some(0.0),
+                ..options("Hello")
+            },
+            PopulationOptions {
+                scale_factor: Some(f64::NAN),
+                ..options("Hello")
+            },
+            PopulationOptions {
+                scale: Some(ScaleKind::Exponential),
+                scale_factor: Some(2.0),
+                ..options(&"a".repeat(1100))
+            },
+            PopulationOptions {
+                max_seconds: Some(-1.0),
+                ..options("Hello")
+            },
+        ];
+        for options in invalid {
+            assert!(options.clone().resolve().is_err(), "{options:?}");
+        }
+
+        // Tournaments never weigh candidates by the scale
+        let tournament = PopulationOptions {
+            selection: Some(SelectionKind::Tournament),
+            scale: Some(ScaleKind::Order),
+            scale_factor: Some(-1.0),
+            ..options("Hello")
+        };
+        assert!(tournament.resolve().is_ok());
+    }
+}
@@ -0,0 +1,53 @@
+use clap::{Parser, Subcommand};
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+mod commands;
+mod config;
+mod output;
+mod tui;
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Evolves random strings towards a target term, headless.
+#[derive(Parser)]
+#[command(name = "monkey", version)]
+struct Cli {
+    /// TOML or JSON file with `[population]` and `[sweep]` tables, overridden by flags
+    #[arg(long, short, global = true)]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Simulates one population with live progress
+    Run(commands::run::RunArgs),
+    /// Simulates a grid of mutation rates and population sizes
+    Sweep(commands::sweep::SweepArgs),
+    /// Measures the time per generation
+    Bench(commands::bench::BenchArgs),
+    /// Solves a long target term in segments and stitches them together
+    Segmented(commands::segmented::SegmentedArgs),
+    /// Re-simulates a run recorded by `run --output`
+    Replay(commands::replay::ReplayArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result =
+        config::ConfigFile::load(cli.config.as_deref()).and_then(|config| match cli.command {
+            Command::Run(args) => commands::run::run(args, config),
+            Command::Sweep(args) => commands::sweep::sweep(args, config),
+            Command::Bench(args) => commands::bench::bench(args, config),
+            Command::Segmented(args) => commands::segmented::segmented(args, config),
+            Command::Replay(args) => commands::replay::replay(args),
+        });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use shakespeare_monkey_solver::generation_log::{LogFormat, RowWriter};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter},
+    path::Path,
+};
+
+use crate::{config::Settings, Result};
+
+/// Format of an output file, told apart by its extension.
+pub fn format_of(path: &Path) -> Result<LogFormat> {
+    LogFormat::from_path(path).ok_or_else(|| {
+        format!(
+            "unsupported output file {}, expected a .csv or .jsonl extension",
+            path.display()
+        )
+        .into()
+    })
+}
+
+pub fn create(path: &Path) -> Result<BufWriter<File>> {
+    let file =
+        File::create(path).map_err(|err| format!("failed to create {}: {err}", path.display()))?;
+    Ok(BufWriter::new(file))
+}
+
+/// Rows written to `path` after a header recording `settings`.
+pub fn create_rows(path: &Path, settings: &Settings) -> Result<RowWriter<BufWriter<File>>> {
+    let format = format_of(path)?;
+    Ok(RowWriter::new(create(path)?, format, settings)?)
+}
+
+/// Settings recorded in the header of a file written by `create_rows` or a `GenerationLog`.
+pub fn read_settings(path: &Path) -> Result<Settings> {
+    let file =
+        File::open(path).map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+    let mut first_line = String::new();
+    BufReader::new(file).read_line(&mut first_line)?;
+    let first_line = first_line.trim_end();
+    let settings = match first_line.strip_prefix("# config: ") {
+        Some(config) => serde_json::from_str(config).ok(),
+        None => serde_json::from_str::<serde_json::Value>(first_line)
+            .ok()
+            .and_then(|mut header| serde_json::from_value(header["config"].take()).ok()),
+    };
+    settings.ok_or_else(|| format!("{} does not start with a config header", path.display()).into())
+}
@@ -0,0 +1,363 @@
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListState, Paragraph, Sparkline, Wrap},
+    DefaultTerminal, Frame,
+};
+use shakespeare_monkey_solver::{
+    dna::Dna,
+    population::Population,
+    run::{RunOutcome, RunResult},
+    stats::GenerationStats,
+};
+use std::{
+    collections::VecDeque,
+    io::{self, IsTerminal},
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// Pause between generations at every speed, fastest first.
+const DELAYS: [Duration; 6] = [
+    Duration::ZERO,
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(50),
+    Duration::from_millis(200),
+    Duration::from_secs(1),
+];
+/// How often the screen is redrawn and input is read at full speed.
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+/// How long generations are counted before the speed readout is updated.
+const RATE_INTERVAL: Duration = Duration::from_millis(500);
+/// Generations of fitness history kept for the sparklines, more than any terminal is wide.
+const HISTORY: usize = 1024;
+
+/// Terminal UI following a `Population::run_with` loop through `on_generation`. The terminal
+/// is restored when it is dropped.
+pub struct Tui {
+    terminal: DefaultTerminal,
+    view: View,
+}
+
+/// State kept between frames: fitness history, controls and scroll position.
+struct View {
+    /// Best and mean fitness of the latest generations.
+    history: VecDeque<(usize, f64)>,
+    paused: bool,
+    /// Lets one generation through, then pauses again.
+    stepping: bool,
+    quit: bool,
+    speed: usize,
+    selected: usize,
+    offset: usize,
+    last_frame: Option<Instant>,
+    rate_sample: (usize, Instant),
+    generations_per_second: f64,
+}
+
+/// Where the run loop is, shown in the status line.
+#[derive(Clone, Copy)]
+enum Status {
+    Running,
+    Finished(RunOutcome),
+}
+
+impl Tui {
+    pub fn new() -> io::Result<Self> {
+        if !io::stdout().is_terminal() {
+            return Err(io::Error::other(
+                "the terminal UI needs stdout to be a terminal",
+            ));
+        }
+        let terminal = ratatui::try_init().inspect_err(|_| ratatui::restore())?;
+        Ok(Self {
+            terminal,
+            view: View {
+                history: VecDeque::with_capacity(HISTORY),
+                paused: false,
+                stepping: false,
+                quit: false,
+                speed: 0,
+                selected: 0,
+                offset: 0,
+                last_frame: None,
+                rate_sample: (0, Instant::now()),
+                generations_per_second: 0.0,
+            },
+        })
+    }
+
+    /// Shows one evaluated generation, sleeping for the current speed and blocking while
+    /// paused. Breaks when the user quits.
+    pub fn on_generation(
+        &mut self,
+        population: &Population,
+        stats: &GenerationStats,
+    ) -> io::Result<ControlFlow<()>> {
+        let view = &mut self.view;
+        if view.history.len() == HISTORY {
+            view.history.pop_front();
+        }
+        view.history
+            .push_back((stats.best_fitness, stats.mean_fitness));
+        if view.stepping {
+            view.stepping = false;
+            view.paused = true;
+        }
+
+        let delay = DELAYS[view.speed];
+        let frame_is_due = view
+            .last_frame
+            .is_none_or(|last_frame| last_frame.elapsed() >= FRAME_INTERVAL);
+        if !view.paused && delay.is_zero() && !frame_is_due {
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        let resume_at = Instant::now() + delay;
+        self.draw(population, stats, Status::Running)?;
+        loop {
+            let timeout = if self.view.paused {
+                FRAME_INTERVAL
+            } else {
+                resume_at.saturating_duration_since(Instant::now())
+            };
+            if event::poll(timeout)? {
+                self.handle(event::read()?, population.population.len(), true)?;
+                if self.view.quit {
+                    return Ok(ControlFlow::Break(()));
+                }
+                self.draw(population, stats, Status::Running)?;
+            }
+            if !self.view.paused && Instant::now() >= resume_at {
+                return Ok(ControlFlow::Continue(()));
+            }
+        }
+    }
+
+    /// Shows the final generation until the user quits.
+    pub fn finish(mut self, population: &Population, result: &RunResult) -> io::Result<()> {
+        let stats = population.stats();
+        self.view.generations_per_second =
+            result.generations as f64 / result.elapsed.as_secs_f64().max(f64::EPSILON);
+        while !self.view.quit {
+            self.draw(population, &stats, Status::Finished(result.outcome))?;
+            self.handle(event::read()?, population.population.len(), false)?;
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, event: Event, population_size: usize, running: bool) -> io::Result<()> {
+        let page = (self.terminal.size()?.height / 2).max(1) as usize;
+        self.view.handle(event, population_size, running, page);
+        Ok(())
+    }
+
+    fn draw(
+        &mut self,
+        population: &Population,
+        stats: &GenerationStats,
+        status: Status,
+    ) -> io::Result<()> {
+        if let Status::Running = status {
+            self.view.sample_rate(stats.generation);
+        }
+        self.terminal
+            .draw(|frame| self.view.render(frame, population, stats, status))?;
+        Ok(())
+    }
+}
+
+impl View {
+    fn handle(&mut self, event: Event, population_size: usize, running: bool, page: usize) {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return;
+        };
+        let last = population_size.saturating_sub(1);
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char(' ') | KeyCode::Char('p') if running => self.paused = !self.paused,
+            KeyCode::Char('n') | KeyCode::Right if running => {
+                self.stepping = self.paused;
+                self.paused = !self.paused;
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Char('-') => self.speed = (self.speed + 1).min(DELAYS.len() - 1),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(page),
+            KeyCode::PageDown => self.selected = (self.selected + page).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            _ => {}
+        }
+    }
+
+    fn sample_rate(&mut self, generation: usize) {
+        let now = Instant::now();
+        let (sampled_generation, sampled_at) = self.rate_sample;
+        if now - sampled_at >= RATE_INTERVAL {
+            self.generations_per_second = generation.saturating_sub(sampled_generation) as f64
+                / (now - sampled_at).as_secs_f64();
+            self.rate_sample = (generation, now);
+        }
+        self.last_frame = Some(now);
+    }
+
+    fn render(
+        &mut self,
+        frame: &mut Frame,
+        population: &Population,
+        stats: &GenerationStats,
+        status: Status,
+    ) {
+        let num_genes = population.num_genes();
+        let best = &population.population[stats.best_candidate];
+        let best_width = frame.area().width.saturating_sub(2).max(1) as usize;
+        let best_height = num_genes.div_ceil(best_width) as u16 + 2;
+        let [best_area, status_area, best_fitness_area, mean_fitness_area, population_area, keys_area] =
+            Layout::vertical([
+                Constraint::Length(best_height),
+                Constraint::Length(1),
+                Constraint::Length(4),
+                Constraint::Length(4),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .areas(frame.area());
+
+        // Matched genes of the best candidate in gold, as in the egui demo
+        let best_line =
+            Line::from(genes(best, population.target_genes(), Color::Yellow)).underlined();
+        frame.render_widget(
+            Paragraph::new(best_line).wrap(Wrap { trim: false }).block(
+                Block::bordered().title(format!(" Best of generation {} ", stats.generation)),
+            ),
+            best_area,
+        );
+
+        let state = match status {
+            Status::Running if self.paused => "paused".yellow(),
+            Status::Running => "running".green(),
+            Status::Finished(RunOutcome::Solved) => "solved".yellow().bold(),
+            Status::Finished(outcome) => format!("{outcome:?}").to_lowercase().red(),
+        };
+        let speed = match self.speed {
+            0 => "max".to_owned(),
+            speed => format!("{:?}/gen", DELAYS[speed]),
+        };
+        frame.render_widget(
+            Line::from(vec![
+                format!(
+                    " generation {}  best {}/{num_genes}  mean {:.2}  {:.0} gen/s  speed {speed}  ",
+                    stats.generation,
+                    stats.best_fitness,
+                    stats.mean_fitness,
+                    self.generations_per_second,
+                )
+                .into(),
+                state,
+            ]),
+            status_area,
+        );
+
+        let sparkline = |title: &'static str, area: Rect, fitness: fn(&(usize, f64)) -> f64| {
+            let width = area.width.saturating_sub(2) as usize;
+            let data = self
+                .history
+                .iter()
+                .skip(self.history.len().saturating_sub(width))
+                .map(|entry| (fitness(entry) * 100.0).round() as u64)
+                .collect::<Vec<_>>();
+            Sparkline::default()
+                .data(&data)
+                .max(num_genes as u64 * 100)
+                .style(Style::new().fg(Color::Yellow))
+                .block(Block::bordered().title(title))
+        };
+        frame.render_widget(
+            sparkline(" Best fitness ", best_fitness_area, |&(best, _)| {
+                best as f64
+            }),
+            best_fitness_area,
+        );
+        frame.render_widget(
+            sparkline(" Mean fitness ", mean_fitness_area, |&(_, mean)| mean),
+            mean_fitness_area,
+        );
+
+        // Only the visible candidates are laid out, populations can be large
+        let height = population_area.height.saturating_sub(2).max(1) as usize;
+        self.selected = self
+            .selected
+            .min(population.population.len().saturating_sub(1));
+        self.offset = self
+            .offset
+            .clamp(self.selected.saturating_sub(height - 1), self.selected);
+        let items = population
+            .population
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(idx, candidate)| {
+                let mut spans = vec![Span::raw(format!(
+                    "{idx:>5} {:>4}/{num_genes}  ",
+                    candidate.fitness
+                ))];
+                spans.extend(genes(
+                    candidate,
+                    population.target_genes(),
+                    Color::LightGreen,
+                ));
+                Line::from(spans)
+            });
+        let mut list_state = ListState::default().with_selected(Some(self.selected - self.offset));
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().bg(Color::DarkGray))
+                .block(
+                    Block::bordered()
+                        .title(format!(" Population ({}) ", population.population.len())),
+                ),
+            population_area,
+            &mut list_state,
+        );
+
+        frame.render_widget(
+            Line::from(" space pause  n step  +/- speed  ↑↓ PgUp PgDn scroll  q quit").dark_gray(),
+            keys_area,
+        );
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// Genes of `candidate` with those matching the target in `color`.
+fn genes(candidate: &Dna, target_genes: &[u8], color: Color) -> Vec<Span<'static>> {
+    target_genes
+        .iter()
+        .enumerate()
+        .map(|(locus, &target)| {
+            let span = Span::raw(candidate.gene_char(locus).to_string());
+            if candidate.genes[locus] == target {
+                span.fg(color)
+            } else {
+                span
+            }
+        })
+        .collect()
+}
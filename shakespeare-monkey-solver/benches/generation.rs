@@ -0,0 +1,155 @@
+use shakespeare_monkey_solver::{
+    biased_scale::BiasedScale,
+    dna::encode_term,
+    population::{FitnessEvaluation, Population},
+    utils::{
+        random::Random,
+        sampler::{Sampler, WeightedSampler},
+    },
+};
+use std::time::{Duration, Instant};
+
+const TARGET_TERM: &str =
+    "To be, or not to be, that is the question: Whether 'tis nobler in the mind";
+
+const CASES: [(usize, usize); 8] = [
+    (100, 200),
+    (1_000, 50),
+    (3_000, 30),
+    (10_000, 20),
+    (20_000, 10),
+    (30_000, 10),
+    (50_000, 8),
+    (100_000, 5),
+];
+
+const SAMPLERS: [Sampler; 3] = [Sampler::Cumulative, Sampler::Alias, Sampler::Auto];
+
+const SELECTION_REPEATS: usize = 20;
+
+/// Times only parent selection: one sampler build plus two picks per child.
+fn bench_selection() {
+    for (population_size, _) in CASES {
+        let mut rng = Random::from_seed(0);
+        let weights = (0..population_size)
+            .map(|_| BiasedScale::Order(2.0).scale(rng.gen_range_usize(0..20) as f64))
+            .collect::<Vec<_>>();
+
+        for sampler in SAMPLERS {
+            let start = Instant::now();
+            let mut checksum = 0;
+            for _ in 0..SELECTION_REPEATS {
+                let weighted_sampler = WeightedSampler::create(sampler, weights.iter().copied());
+                for _ in 0..2 * population_size {
+                    checksum ^= weighted_sampler.sample(&mut rng);
+                }
+            }
+            let elapsed = start.elapsed();
+
+            println!(
+                "selection  {:>7}, {:<10}: {:>10.3} ms/generation ({})",
+                population_size,
+                format!("{:?}", sampler),
+                elapsed.as_secs_f64() * 1e3 / SELECTION_REPEATS as f64,
+                checksum % 2
+            );
+        }
+    }
+}
+
+const LONG_TARGET_REPEATS: [usize; 3] = [1, 10, 50];
+
+/// Times whole generations on long targets with either fitness evaluation strategy.
+fn bench_fitness_evaluation() {
+    for repeats in LONG_TARGET_REPEATS {
+        let target_term = TARGET_TERM.repeat(repeats);
+
+        for fitness_evaluation in [FitnessEvaluation::Full, FitnessEvaluation::Incremental] {
+            let mut population =
+                Population::with_seed(target_term.clone(), 1, 500, BiasedScale::Order(2.0), 0);
+            population.fitness_evaluation = fitness_evaluation;
+
+            let (mut fitness_time, mut update_time) = (Duration::ZERO, Duration::ZERO);
+            for _ in 0..50 {
+                let start = Instant::now();
+                population.compute_biased_fitness();
+                fitness_time += start.elapsed();
+
+                let start = Instant::now();
+                population.update_generation();
+                update_time += start.elapsed();
+            }
+
+            println!(
+                "target     {:>7}, {:<11}: {:>10.3} ms fitness + {:>10.3} ms update = {:>10.3} ms/generation",
+                target_term.len(),
+                format!("{:?}", fitness_evaluation),
+                fitness_time.as_secs_f64() * 1e3 / 50.0,
+                update_time.as_secs_f64() * 1e3 / 50.0,
+                (fitness_time + update_time).as_secs_f64() * 1e3 / 50.0
+            );
+        }
+    }
+}
+
+const MATCHES_REPEATS: usize = 20;
+
+/// Times only the full comparison of every candidate's genes with a long target.
+fn bench_locus_matches() {
+    for repeats in LONG_TARGET_REPEATS {
+        let target_term = TARGET_TERM.repeat(repeats);
+        let target_genes = encode_term(&target_term);
+        let mut population =
+            Population::with_seed(target_term.clone(), 1, 500, BiasedScale::Order(2.0), 0)
+                .population;
+
+        let start = Instant::now();
+        let mut checksum = 0;
+        for _ in 0..MATCHES_REPEATS {
+            for candidate in population.iter_mut() {
+                checksum ^= candidate.compute_fitness(&target_genes);
+            }
+        }
+        let elapsed = start.elapsed();
+
+        println!(
+            "matches    {:>7}: {:>10.3} ms/generation ({})",
+            target_term.len(),
+            elapsed.as_secs_f64() * 1e3 / MATCHES_REPEATS as f64,
+            checksum % 2
+        );
+    }
+}
+
+fn main() {
+    bench_selection();
+    bench_fitness_evaluation();
+    bench_locus_matches();
+
+    for (population_size, generations) in CASES {
+        for sampler in SAMPLERS {
+            let mut population = Population::with_seed(
+                TARGET_TERM.to_owned(),
+                2,
+                population_size,
+                BiasedScale::Order(2.0),
+                0,
+            );
+            population.sampler = sampler;
+
+            let start = Instant::now();
+            for _ in 0..generations {
+                population.compute_biased_fitness();
+                population.update_generation();
+            }
+            let elapsed = start.elapsed();
+
+            println!(
+                "population {:>7}, {:<10}: {:>10.3} ms/generation",
+                population_size,
+                format!("{:?}", sampler),
+                elapsed.as_secs_f64() * 1e3 / generations as f64
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use std::{error::Error, fmt};
 use BiasedScale::*;
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BiasedScale {
     Multiplicative(f64),
     Order(f64),
@@ -9,10 +10,84 @@ pub enum BiasedScale {
 
 impl BiasedScale {
     pub fn scale(&self, value: f64) -> f64 {
-        match self {
-            &Multiplicative(factor) => value * factor,
-            &Order(factor) => value.powf(factor),
-            &Exponential(factor) => factor.powf(value),
+        match *self {
+            Multiplicative(factor) => value * factor,
+            Order(factor) => value.powf(factor),
+            Exponential(factor) => factor.powf(value),
         }
     }
+
+    /// Rejects scales under which some candidate of a `num_genes` long target term, or the
+    /// sum over `population_size` of them, would weigh a negative, infinite or NaN amount,
+    /// or under which every candidate would weigh nothing.
+    pub fn validate(&self, num_genes: usize, population_size: usize) -> Result<(), InvalidScale> {
+        let weights = (0..=num_genes)
+            .map(|fitness| self.scale(fitness as f64))
+            .collect::<Vec<_>>();
+        let max_weight = weights.iter().copied().fold(0.0, f64::max);
+        if weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0)
+            && max_weight > 0.0
+            && (max_weight * population_size as f64).is_finite()
+        {
+            Ok(())
+        } else {
+            Err(InvalidScale {
+                biased_scale: *self,
+                num_genes,
+            })
+        }
+    }
+}
+
+/// A scale that cannot weigh the candidates of a population, see `BiasedScale::validate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InvalidScale {
+    pub biased_scale: BiasedScale,
+    pub num_genes: usize,
+}
+
+impl fmt::Display for InvalidScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "scale {:?} gives negative, non-finite or only zero weights for a target term of {} \
+             characters",
+            self.biased_scale, self.num_genes
+        )
+    }
+}
+
+impl Error for InvalidScale {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_unusable_weights() {
+        for biased_scale in [
+            Multiplicative(1.4),
+            Order(2.0),
+            Order(0.0),
+            Exponential(1.5),
+            Exponential(0.0),
+        ] {
+            assert_eq!(biased_scale.validate(40, 1000), Ok(()), "{biased_scale:?}");
+        }
+        for biased_scale in [
+            Multiplicative(-1.0),
+            Multiplicative(0.0),
+            Order(-1.0),
+            Exponential(-2.0),
+            Exponential(f64::NAN),
+            Exponential(1e10),
+        ] {
+            assert!(biased_scale.validate(40, 1000).is_err(), "{biased_scale:?}");
+        }
+        // Fine for each candidate, but not summed over the population
+        assert!(Exponential(1.5).validate(1700, 1).is_ok());
+        assert!(Exponential(1.5).validate(1700, usize::MAX).is_err());
+    }
 }
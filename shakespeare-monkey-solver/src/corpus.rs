@@ -0,0 +1,202 @@
+use super::dna::{encode_gene, UNKNOWN_GENE};
+use crate::utils::random::Random;
+use std::{fs, io, path::Path};
+
+/// Sonnets and speeches from the plays, one line of verse per line and a blank line between
+/// passages, so `Split::Lines` gives a target per line of verse.
+const SHAKESPEARE: &str = include_str!("../corpus/shakespeare.txt");
+
+/// How a text is cut into targets. Runs of whitespace, line breaks included, become single
+/// spaces in every target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Split {
+    /// Every non-blank line.
+    #[default]
+    Lines,
+    /// Runs of text ending in `.`, `!` or `?` before whitespace, across line breaks.
+    Sentences,
+    /// Consecutive pieces of this many characters of the whole text, the last one shorter.
+    /// Whitespace is collapsed before the text is cut, so pieces may start or end in a space.
+    Chunks(usize),
+}
+
+/// Target terms cut from a text, to pick from by index, at random or one after another.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Corpus {
+    targets: Vec<String>,
+}
+
+impl Corpus {
+    pub fn from_text(text: &str, split: Split) -> Self {
+        let targets = match split {
+            Split::Lines => text.lines().map(collapse_whitespace).collect(),
+            Split::Sentences => sentences(&collapse_whitespace(text)),
+            Split::Chunks(size) => collapse_whitespace(text)
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(size.max(1))
+                .map(|chunk| chunk.iter().collect())
+                .collect(),
+        };
+        Self { targets }.without_empty()
+    }
+
+    pub fn load(path: impl AsRef<Path>, split: Split) -> io::Result<Self> {
+        Ok(Self::from_text(&fs::read_to_string(path)?, split))
+    }
+
+    /// The public-domain Shakespeare sample bundled with the crate.
+    pub fn shakespeare(split: Split) -> Self {
+        Self::from_text(SHAKESPEARE, split)
+    }
+
+    /// Drops the characters no gene can match, such as `;` and `?`, from every target, so
+    /// every target can be solved.
+    pub fn retain_encodable(self) -> Self {
+        let targets = self
+            .targets
+            .iter()
+            .map(|target| {
+                let encodable = target
+                    .chars()
+                    .filter(|&token| encode_gene(token) != UNKNOWN_GENE)
+                    .collect::<String>();
+                collapse_whitespace(&encodable)
+            })
+            .collect();
+        Self { targets }.without_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.targets.get(idx).map(String::as_str)
+    }
+
+    /// A target drawn uniformly at random, `None` for an empty corpus.
+    pub fn pick(&self, rng: &mut Random) -> Option<&str> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get(rng.gen_range_usize(0..self.len()))
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.targets.iter().map(String::as_str)
+    }
+
+    pub fn targets(&self) -> &[String] {
+        &self.targets
+    }
+
+    fn without_empty(mut self) -> Self {
+        self.targets.retain(|target| !target.is_empty());
+        self
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, token)) = chars.next() {
+        let ends_sentence = matches!(token, '.' | '!' | '?')
+            && chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+        if ends_sentence {
+            let end = idx + token.len_utf8();
+            sentences.push(text[start..end].trim().to_owned());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim().to_owned());
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Now is the winter   of our discontent\n\n\
+                        Made glorious summer. By this sun of York!\n  \n\
+                        And all the clouds? That lour'd upon our house\n";
+
+    #[test]
+    fn lines_skip_blank_lines_and_collapse_whitespace() {
+        let corpus = Corpus::from_text(TEXT, Split::Lines);
+        assert_eq!(
+            corpus.targets(),
+            [
+                "Now is the winter of our discontent",
+                "Made glorious summer. By this sun of York!",
+                "And all the clouds? That lour'd upon our house",
+            ]
+        );
+    }
+
+    #[test]
+    fn sentences_run_across_line_breaks() {
+        let corpus = Corpus::from_text(TEXT, Split::Sentences);
+        assert_eq!(
+            corpus.targets(),
+            [
+                "Now is the winter of our discontent Made glorious summer.",
+                "By this sun of York!",
+                "And all the clouds?",
+                "That lour'd upon our house",
+            ]
+        );
+        // Only punctuation before whitespace or the end ends a sentence
+        let corpus = Corpus::from_text("Wait...what? Yes.", Split::Sentences);
+        assert_eq!(corpus.targets(), ["Wait...what?", "Yes."]);
+    }
+
+    #[test]
+    fn chunks_have_the_same_length_but_the_last() {
+        let corpus = Corpus::from_text(TEXT, Split::Chunks(10));
+        let text = collapse_whitespace(TEXT);
+        assert_eq!(corpus.targets().concat(), text);
+        let (last, chunks) = corpus.targets().split_last().unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() == 10));
+        assert!((1..=10).contains(&last.chars().count()));
+        // Pieces keep the spaces they are cut at
+        assert_eq!(corpus.get(1), Some(" winter of"));
+
+        assert_eq!(
+            Corpus::from_text("abc", Split::Chunks(0)).targets(),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn retain_encodable_drops_unmatchable_characters() {
+        let corpus = Corpus::from_text("To be; or not ?\n;;;\n", Split::Lines).retain_encodable();
+        assert_eq!(corpus.targets(), ["To be or not"]);
+    }
+
+    #[test]
+    fn shakespeare_gives_a_target_per_line_of_verse() {
+        let corpus = Corpus::shakespeare(Split::default());
+        assert_eq!(
+            corpus.len(),
+            SHAKESPEARE
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count()
+        );
+        assert_eq!(
+            corpus.get(0),
+            Some("Shall I compare thee to a summer's day?")
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::{RNG};
+use crate::utils::random::Random;
 
 const SPECIAL_SYMBOLS: [char; 14] = [
     ' ', '-', '(', ')', '[', ']', '"', '\'', '/', '.', ',', '_', '!', ':',
@@ -34,63 +34,444 @@ const fn generate_charset() -> [char; 26 * 2 + SPECIAL_SYMBOLS.len()] {
 
 static CHARSET: [char; 26 * 2 + SPECIAL_SYMBOLS.len()] = generate_charset();
 
-fn gen_random_char() -> char {
-    CHARSET[RNG.lock().unwrap().gen_range_usize(0..CHARSET.len())]
+/// Gene used for target characters outside of `CHARSET`, which no candidate can ever match.
+pub const UNKNOWN_GENE: u8 = u8::MAX;
+
+pub fn encode_gene(token: char) -> u8 {
+    CHARSET
+        .iter()
+        .position(|&gene| gene == token)
+        .map_or(UNKNOWN_GENE, |idx| idx as u8)
+}
+
+pub fn decode_gene(gene: u8) -> char {
+    CHARSET.get(gene as usize).copied().unwrap_or('?')
+}
+
+pub fn encode_term(term: &str) -> Vec<u8> {
+    term.chars().map(encode_gene).collect()
+}
+
+pub fn decode_genes(genes: &[u8]) -> String {
+    genes.iter().copied().map(decode_gene).collect()
+}
+
+fn gen_random_gene(rng: &mut Random) -> u8 {
+    rng.gen_range_usize(0..CHARSET.len()) as u8
+}
+
+const LOCI_PER_WORD: usize = u64::BITS as usize;
+
+/// Whether each locus matches the target, packed 64 loci per word, so a child's fitness can
+/// be derived from its parents' segments instead of rescanning its genes.
+#[derive(Clone, Debug, Default)]
+pub struct LocusMatches(Vec<u64>);
+
+impl LocusMatches {
+    fn compute(&mut self, genes: &[u8], target_genes: &[u8]) {
+        self.0.clear();
+        let (genes, target_genes) = (
+            genes.chunks(LOCI_PER_WORD),
+            target_genes.chunks(LOCI_PER_WORD),
+        );
+        self.0
+            .extend(genes.zip(target_genes).map(|(genes, target_genes)| {
+                match (
+                    <&[u8; LOCI_PER_WORD]>::try_from(genes),
+                    target_genes.try_into(),
+                ) {
+                    (Ok(genes), Ok(target_genes)) => Self::word(genes, target_genes),
+                    _ => Self::partial_word(genes, target_genes),
+                }
+            }));
+    }
+
+    /// Compares a whole word's loci without branching, so the compiler vectorises the
+    /// compares. Multiplying eight 0-or-1 bytes by the magic constant gathers them into the
+    /// top byte, eight bits of the word at a time; `cargo bench --bench generation` times it
+    /// about six times faster than setting the bits one by one.
+    fn word(genes: &[u8; LOCI_PER_WORD], target_genes: &[u8; LOCI_PER_WORD]) -> u64 {
+        let mut is_match = [0; LOCI_PER_WORD];
+        for locus in 0..LOCI_PER_WORD {
+            is_match[locus] = (genes[locus] == target_genes[locus]) as u8;
+        }
+        is_match
+            .chunks_exact(8)
+            .enumerate()
+            .fold(0, |word, (byte, is_match)| {
+                let is_match = u64::from_le_bytes(is_match.try_into().unwrap());
+                word | (is_match.wrapping_mul(0x0102_0408_1020_4080) >> 56) << (8 * byte)
+            })
+    }
+
+    /// The last, shorter word.
+    fn partial_word(genes: &[u8], target_genes: &[u8]) -> u64 {
+        genes
+            .iter()
+            .zip(target_genes)
+            .enumerate()
+            .fold(0, |word, (bit, (gene, token))| {
+                word | ((gene == token) as u64) << bit
+            })
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Number of matching loci in `0..locus`.
+    pub fn count_before(&self, locus: usize) -> usize {
+        let (full_words, bits) = (locus / LOCI_PER_WORD, locus % LOCI_PER_WORD);
+        let partial = match bits {
+            0 => 0,
+            _ => (self.0[full_words] & ((1 << bits) - 1)).count_ones() as usize,
+        };
+
+        self.0[..full_words]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>()
+            + partial
+    }
+
+    pub fn is_match(&self, locus: usize) -> bool {
+        self.0[locus / LOCI_PER_WORD] & (1 << (locus % LOCI_PER_WORD)) != 0
+    }
+
+    fn set(&mut self, locus: usize, is_match: bool) {
+        let (word, bit) = (locus / LOCI_PER_WORD, locus % LOCI_PER_WORD);
+        self.0[word] = self.0[word] & !(1 << bit) | (is_match as u64) << bit;
+    }
+
+    /// Number of matching loci in `segment`.
+    pub fn count_in(&self, segment: CrossoverSegment) -> usize {
+        self.count_before(segment.end) - self.count_before(segment.start)
+    }
+
+    /// Takes loci in `segment` from `inner` and the rest from `outer`.
+    fn splice(&mut self, outer: &Self, inner: &Self, segment: CrossoverSegment) {
+        self.0.clone_from(&outer.0);
+        if segment.start >= segment.end {
+            return;
+        }
+
+        for word in segment.start / LOCI_PER_WORD..=(segment.end - 1) / LOCI_PER_WORD {
+            let offset = word * LOCI_PER_WORD;
+            let start = segment.start.saturating_sub(offset);
+            let end = (segment.end - offset).min(LOCI_PER_WORD);
+            let mask = (u64::MAX >> (LOCI_PER_WORD - (end - start))) << start;
+            self.0[word] = self.0[word] & !mask | inner.0[word] & mask;
+        }
+    }
+}
+
+/// Loci `start..end` of a child, which are taken from `partner_b` while the rest come
+/// from `partner_a`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CrossoverSegment {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl CrossoverSegment {
+    /// Genes up to and including `midpoint` come from `partner_b`.
+    pub fn single_point(midpoint: usize, num_genes: usize) -> Self {
+        Self {
+            start: 0,
+            end: (midpoint + 1).min(num_genes),
+        }
+    }
+
+    /// The same segment `offset` loci further on.
+    pub fn shifted(self, offset: usize) -> Self {
+        Self {
+            start: self.start + offset,
+            end: self.end + offset,
+        }
+    }
+
+    pub fn contains(&self, locus: usize) -> bool {
+        (self.start..self.end).contains(&locus)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Crossover {
+    /// A prefix of at least one gene from `partner_b`.
+    #[default]
+    SinglePoint,
+    /// A possibly empty run of genes anywhere in the genome from `partner_b`.
+    TwoPoint,
 }
 
+impl Crossover {
+    pub fn gen_segment(self, num_genes: usize, rng: &mut Random) -> CrossoverSegment {
+        match self {
+            Crossover::SinglePoint => {
+                CrossoverSegment::single_point(rng.gen_range_usize(0..num_genes), num_genes)
+            }
+            Crossover::TwoPoint => {
+                let (a, b) = (
+                    rng.gen_range_usize(0..num_genes + 1),
+                    rng.gen_range_usize(0..num_genes + 1),
+                );
+                CrossoverSegment {
+                    start: a.min(b),
+                    end: a.max(b),
+                }
+            }
+        }
+    }
+}
+
+/// Genes are stored as indices into the solver's charset, see `encode_gene` and `decode_gene`.
 #[derive(Clone, Debug)]
 pub struct Dna {
-    pub genes: Vec<char>,
+    pub genes: Vec<u8>,
     pub fitness: usize,
     pub biased_fitness: f64,
+    pub matches: LocusMatches,
 }
 
 impl Dna {
-    pub fn crate_random_genes(num_genes: usize) -> Self {
-        Self {
-            genes: std::iter::repeat_with(gen_random_char)
+    pub fn crate_random_genes(num_genes: usize, rng: &mut Random) -> Self {
+        Self::from_genes(
+            std::iter::repeat_with(|| gen_random_gene(rng))
                 .take(num_genes)
                 .collect(),
+        )
+    }
+
+    pub fn from_genes(genes: Vec<u8>) -> Self {
+        Self {
+            genes,
             fitness: 0,
             biased_fitness: 0.0,
+            matches: LocusMatches::default(),
         }
     }
 
-    pub fn compute_fitness(&mut self, target_term: &String) -> usize {
-        self.fitness = target_term
-            .char_indices()
-            .filter(|&(idx, token)| self.genes[idx] == token)
-            .count();
+    pub fn gene_char(&self, locus: usize) -> char {
+        decode_gene(self.genes[locus])
+    }
+
+    pub fn compute_fitness(&mut self, target_genes: &[u8]) -> usize {
+        self.matches.compute(&self.genes, target_genes);
+        self.fitness = self.matches.count();
         self.fitness
     }
 
-    pub fn crossover(partner_a: &Self, partner_b: &Self) -> Self {
-        let mut child = Self {
-            genes: Vec::with_capacity(partner_a.genes.len()),
-            fitness: 0,
-            biased_fitness: 0.0,
-        };
+    /// Derives the fitness of a child built by `crossover_into` from the parents' `matches`,
+    /// which must be up to date. Mutations are accounted for afterwards with `refresh_locus`.
+    pub fn inherit_fitness(
+        child: &mut Self,
+        partner_a: &Self,
+        partner_b: &Self,
+        segment: CrossoverSegment,
+    ) {
+        child
+            .matches
+            .splice(&partner_a.matches, &partner_b.matches, segment);
+        child.fitness = partner_a.fitness - partner_a.matches.count_in(segment)
+            + partner_b.matches.count_in(segment);
+    }
 
-        let midpoint = RNG
-            .lock()
-            .unwrap()
-            .gen_range_usize(0..partner_a.genes.len());
-
-        for i in 0..partner_a.genes.len() {
-            child.genes.push(if i > midpoint {
-                partner_a.genes[i]
-            } else {
-                partner_b.genes[i]
-            });
+    /// Updates `matches` and `fitness` after the gene at `locus` changed.
+    pub fn refresh_locus(&mut self, locus: usize, target_genes: &[u8]) {
+        let is_match = self.genes[locus] == target_genes[locus];
+        match (self.matches.is_match(locus), is_match) {
+            (false, true) => self.fitness += 1,
+            (true, false) => self.fitness -= 1,
+            _ => return,
         }
+        self.matches.set(locus, is_match);
+    }
+
+    /// Single-point crossover, see `Crossover::SinglePoint`.
+    pub fn crossover(partner_a: &Self, partner_b: &Self, rng: &mut Random) -> Self {
+        Self::crossover_at(
+            partner_a,
+            partner_b,
+            Crossover::SinglePoint.gen_segment(partner_a.genes.len(), rng),
+        )
+    }
 
+    /// Genes in `segment` come from `partner_b`, the rest from `partner_a`.
+    pub fn crossover_at(partner_a: &Self, partner_b: &Self, segment: CrossoverSegment) -> Self {
+        let mut child = Self::from_genes(Vec::with_capacity(partner_a.genes.len()));
+        Self::crossover_into(&mut child, partner_a, partner_b, segment);
         child
     }
 
-    pub fn mutate(&mut self, mutation_rate: usize) {
+    /// Same as `crossover_at`, but overwrites `child` so its gene buffer can be reused.
+    pub fn crossover_into(
+        child: &mut Self,
+        partner_a: &Self,
+        partner_b: &Self,
+        segment: CrossoverSegment,
+    ) {
+        let CrossoverSegment { start, end } = segment;
+
+        child.genes.resize(partner_a.genes.len(), 0);
+        child.genes[..start].copy_from_slice(&partner_a.genes[..start]);
+        child.genes[start..end].copy_from_slice(&partner_b.genes[start..end]);
+        child.genes[end..].copy_from_slice(&partner_a.genes[end..]);
+        child.fitness = 0;
+        child.biased_fitness = 0.0;
+    }
+
+    pub fn mutate(&mut self, mutation_rate: usize, rng: &mut Random) {
+        self.mutate_with(mutation_rate, rng, |_, _| ());
+    }
+
+    /// Same as `mutate`, but reports every mutated locus and its new gene to `on_mutation`.
+    pub fn mutate_with(
+        &mut self,
+        mutation_rate: usize,
+        rng: &mut Random,
+        on_mutation: impl FnMut(usize, u8),
+    ) {
+        self.mutate_from(0, mutation_rate, rng, on_mutation);
+    }
+
+    /// Same as `mutate_with`, but leaves the loci before `first_locus` alone.
+    pub fn mutate_from(
+        &mut self,
+        first_locus: usize,
+        mutation_rate: usize,
+        rng: &mut Random,
+        mut on_mutation: impl FnMut(usize, u8),
+    ) {
+        let first_locus = first_locus.min(self.genes.len());
+        for (locus, gene) in self.genes.iter_mut().enumerate().skip(first_locus) {
+            if rng.gen_range_usize(0..101) < mutation_rate {
+                *gene = gen_random_gene(rng);
+                on_mutation(locus, *gene);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Dna {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.genes
-            .iter_mut()
-            .filter(|_| RNG.lock().unwrap().gen_range_usize(0..101) < mutation_rate)
-            .for_each(|gene| *gene = gen_random_char());
+            .iter()
+            .try_for_each(|&gene| write!(f, "{}", decode_gene(gene)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn genes_round_trip_through_the_charset() {
+        let term = "To be, or not to be: that is the question!";
+        assert_eq!(decode_genes(&encode_term(term)), term);
+        assert!(CHARSET
+            .iter()
+            .all(|&token| decode_gene(encode_gene(token)) == token));
+        assert_eq!(encode_gene('~'), UNKNOWN_GENE);
+        assert_eq!(decode_gene(UNKNOWN_GENE), '?');
+    }
+
+    #[test]
+    fn crossover_segments_stay_in_bounds() {
+        let mut rng = Random::from_seed(5);
+        for num_genes in [1, 2, 11, 200] {
+            for _ in 0..500 {
+                let segment = Crossover::SinglePoint.gen_segment(num_genes, &mut rng);
+                assert_eq!(segment.start, 0);
+                assert!((1..=num_genes).contains(&segment.end), "{segment:?}");
+
+                let segment = Crossover::TwoPoint.gen_segment(num_genes, &mut rng);
+                assert!(segment.start <= segment.end && segment.end <= num_genes);
+            }
+        }
+    }
+
+    #[test]
+    fn crossover_into_overwrites_reused_children() {
+        let mut rng = Random::from_seed(9);
+        let partner_a = Dna::crate_random_genes(40, &mut rng);
+        let partner_b = Dna::crate_random_genes(40, &mut rng);
+        let mut child = Dna::crate_random_genes(40, &mut rng);
+        for _ in 0..100 {
+            let segment = Crossover::TwoPoint.gen_segment(40, &mut rng);
+            Dna::crossover_into(&mut child, &partner_a, &partner_b, segment);
+            assert_eq!(
+                child.genes,
+                Dna::crossover_at(&partner_a, &partner_b, segment).genes
+            );
+            for (locus, &gene) in child.genes.iter().enumerate() {
+                let parent = if segment.contains(locus) {
+                    &partner_b
+                } else {
+                    &partner_a
+                };
+                assert_eq!(gene, parent.genes[locus], "{segment:?} at {locus}");
+            }
+        }
+    }
+
+    #[test]
+    fn locus_matches_flag_every_matching_locus() {
+        let mut rng = Random::from_seed(4);
+        for num_genes in [0, 1, 8, 63, 64, 65, 200] {
+            let target_genes = Dna::crate_random_genes(num_genes, &mut rng).genes;
+            let mut dna = Dna::crate_random_genes(num_genes, &mut rng);
+            for (gene, &token) in dna.genes.iter_mut().zip(&target_genes) {
+                if rng.gen_range_usize(0..2) == 0 {
+                    *gene = token;
+                }
+            }
+
+            dna.compute_fitness(&target_genes);
+            for (locus, (gene, token)) in dna.genes.iter().zip(&target_genes).enumerate() {
+                assert_eq!(
+                    dna.matches.is_match(locus),
+                    gene == token,
+                    "{num_genes} genes, locus {locus}"
+                );
+            }
+            let matches = dna.genes.iter().zip(&target_genes).filter(|(a, b)| a == b);
+            assert_eq!(dna.fitness, matches.count());
+        }
+    }
+
+    #[test]
+    fn inherited_fitness_matches_rescan() {
+        let mut rng = Random::from_seed(3);
+        let num_genes = 150;
+        let target_genes = Dna::crate_random_genes(num_genes, &mut rng).genes;
+        let mut partner_a = Dna::crate_random_genes(num_genes, &mut rng);
+        let mut partner_b = Dna::from_genes(target_genes.clone());
+        partner_b.genes[70] = (target_genes[70] + 1) % CHARSET.len() as u8;
+        partner_a.compute_fitness(&target_genes);
+        partner_b.compute_fitness(&target_genes);
+
+        // Segments starting and ending on either side of, and exactly at, word boundaries
+        let bounds = [0, 1, 63, 64, 65, 100, 127, 128, 129, num_genes];
+        let mut child = Dna::from_genes(Vec::new());
+        for &start in &bounds {
+            for &end in bounds.iter().filter(|&&end| end >= start) {
+                let segment = CrossoverSegment { start, end };
+                Dna::crossover_into(&mut child, &partner_a, &partner_b, segment);
+                Dna::inherit_fitness(&mut child, &partner_a, &partner_b, segment);
+                let mut rescanned = child.clone();
+                rescanned.compute_fitness(&target_genes);
+                assert_eq!(child.fitness, rescanned.fitness, "{segment:?}");
+                assert_eq!(child.matches.0, rescanned.matches.0, "{segment:?}");
+
+                for locus in [start.min(num_genes - 1), 64, 70, num_genes - 1] {
+                    child.genes[locus] = target_genes[locus];
+                    child.refresh_locus(locus, &target_genes);
+                }
+                rescanned.genes.clone_from(&child.genes);
+                rescanned.compute_fitness(&target_genes);
+                assert_eq!(child.fitness, rescanned.fitness, "{segment:?}");
+                assert_eq!(child.matches.0, rescanned.matches.0, "{segment:?}");
+            }
+        }
     }
 }
@@ -0,0 +1,169 @@
+use super::dna::{CrossoverSegment, Dna};
+
+/// Compact record of every reproduction step taken by a `Population`, enough to
+/// rebuild any recorded generation from the population the recording started with.
+#[derive(Clone, Debug, Default)]
+pub struct EventLog {
+    initial_population: Vec<Vec<u8>>,
+    generations: Vec<GenerationEvents>,
+}
+
+/// Parent indices, crossover segments and mutations of every child in one generation.
+#[derive(Clone, Debug, Default)]
+pub struct GenerationEvents {
+    parents: Vec<[u32; 2]>,
+    crossover_segments: Vec<[u32; 2]>,
+    mutation_offsets: Vec<u32>,
+    mutations: Vec<(u32, u8)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ChildEvent<'a> {
+    pub parents: [usize; 2],
+    pub crossover_segment: CrossoverSegment,
+    pub mutations: &'a [(u32, u8)],
+}
+
+impl EventLog {
+    pub fn new(population: &[Dna]) -> Self {
+        Self {
+            initial_population: population
+                .iter()
+                .map(|candidate| candidate.genes.clone())
+                .collect(),
+            generations: Vec::new(),
+        }
+    }
+
+    pub fn begin_generation(&mut self) -> &mut GenerationEvents {
+        self.generations.push(GenerationEvents::default());
+        self.generations.last_mut().unwrap()
+    }
+
+    pub fn num_generations(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub fn generation(&self, generation: usize) -> Option<&GenerationEvents> {
+        self.generations.get(generation)
+    }
+
+    pub fn generations(&self) -> &[GenerationEvents] {
+        &self.generations
+    }
+
+    /// Rebuilds the genes of the population after `generation` recorded updates.
+    /// Generation `0` is the population the recording started with.
+    pub fn replay(&self, generation: usize) -> Option<Vec<Dna>> {
+        if generation > self.generations.len() {
+            return None;
+        }
+
+        let mut population = self
+            .initial_population
+            .iter()
+            .cloned()
+            .map(Dna::from_genes)
+            .collect::<Vec<_>>();
+
+        for events in &self.generations[..generation] {
+            population = events
+                .children()
+                .map(|event| {
+                    let [parent_a, parent_b] = event.parents;
+                    let mut child = Dna::crossover_at(
+                        &population[parent_a],
+                        &population[parent_b],
+                        event.crossover_segment,
+                    );
+                    for &(locus, gene) in event.mutations {
+                        child.genes[locus as usize] = gene;
+                    }
+                    child
+                })
+                .collect();
+        }
+
+        Some(population)
+    }
+}
+
+impl GenerationEvents {
+    pub fn record_child(
+        &mut self,
+        parent_a: usize,
+        parent_b: usize,
+        crossover_segment: CrossoverSegment,
+    ) {
+        self.parents.push([parent_a as u32, parent_b as u32]);
+        self.crossover_segments
+            .push([crossover_segment.start as u32, crossover_segment.end as u32]);
+        self.mutation_offsets.push(self.mutations.len() as u32);
+    }
+
+    /// Attributes a mutation to the child most recently passed to `record_child`.
+    pub fn record_mutation(&mut self, locus: usize, gene: u8) {
+        self.mutations.push((locus as u32, gene));
+    }
+
+    pub fn num_children(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn child(&self, idx: usize) -> Option<ChildEvent<'_>> {
+        let mutations_start = *self.mutation_offsets.get(idx)? as usize;
+        let mutations_end = self
+            .mutation_offsets
+            .get(idx + 1)
+            .map_or(self.mutations.len(), |&offset| offset as usize);
+
+        let [parent_a, parent_b] = self.parents[idx];
+        let [start, end] = self.crossover_segments[idx];
+        Some(ChildEvent {
+            parents: [parent_a as usize, parent_b as usize],
+            crossover_segment: CrossoverSegment {
+                start: start as usize,
+                end: end as usize,
+            },
+            mutations: &self.mutations[mutations_start..mutations_end],
+        })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = ChildEvent<'_>> {
+        (0..self.num_children()).filter_map(|idx| self.child(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{biased_scale::BiasedScale, dna::Crossover, population::Population};
+
+    #[test]
+    fn replay_rebuilds_every_generation() {
+        for crossover in [Crossover::SinglePoint, Crossover::TwoPoint] {
+            let mut population =
+                Population::with_seed("Hello World".to_owned(), 5, 30, BiasedScale::Order(2.0), 7);
+            population.crossover = crossover;
+            population.elitism = 1;
+            population.record_events();
+
+            let mut generations = vec![population.population.clone()];
+            for _ in 0..20 {
+                population.compute_biased_fitness();
+                population.update_generation();
+                generations.push(population.population.clone());
+            }
+
+            let event_log = population.event_log.as_ref().unwrap();
+            assert_eq!(event_log.num_generations(), 20);
+            for (generation, expected) in generations.iter().enumerate() {
+                let replayed = event_log.replay(generation).unwrap();
+                assert!(replayed
+                    .iter()
+                    .map(|candidate| &candidate.genes)
+                    .eq(expected.iter().map(|candidate| &candidate.genes)));
+            }
+            assert!(event_log.replay(21).is_none());
+        }
+    }
+}
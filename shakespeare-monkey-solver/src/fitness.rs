@@ -0,0 +1,9 @@
+use super::dna::Dna;
+
+/// Scores a whole generation at once. When a `Population` has one, its scores replace
+/// `biased_scale` as the weights parents are selected and elites ranked by, while
+/// `Dna::fitness` keeps counting matching loci for stats and the solved check.
+pub trait FitnessFunction: Send {
+    /// Pushes one non-negative score per candidate onto `scores`, which starts out empty.
+    fn score(&mut self, population: &[Dna], scores: &mut Vec<f64>);
+}
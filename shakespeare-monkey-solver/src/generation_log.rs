@@ -0,0 +1,287 @@
+use super::{population::Population, stats::GenerationStats};
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    path::Path,
+    time::Instant,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Comma-separated values after a `# config: {...}` comment line, so readers that skip
+    /// `#` comments load the rows as a table.
+    Csv,
+    /// One JSON object per line, the first being `{"config": {...}}`.
+    JsonLines,
+}
+
+impl LogFormat {
+    /// Format named by the extension of `path`, `.csv` or `.jsonl`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "csv" => Some(Self::Csv),
+            "jsonl" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+/// Serialized rows after a header line holding the config they were produced with, laid out
+/// as described on `LogFormat`. Writers are not buffered here.
+pub enum RowWriter<W: Write> {
+    Csv(Box<csv::Writer<W>>),
+    JsonLines(W),
+}
+
+impl<W: Write> RowWriter<W> {
+    pub fn new(writer: W, format: LogFormat, config: &impl Serialize) -> io::Result<Self> {
+        Self::with_header(writer, format, &serde_json::to_string(config)?)
+    }
+
+    fn with_header(mut writer: W, format: LogFormat, config: &str) -> io::Result<Self> {
+        match format {
+            LogFormat::Csv => {
+                writeln!(writer, "# config: {config}")?;
+                Ok(Self::Csv(Box::new(csv::Writer::from_writer(writer))))
+            }
+            LogFormat::JsonLines => {
+                writeln!(writer, "{{\"config\":{config}}}")?;
+                Ok(Self::JsonLines(writer))
+            }
+        }
+    }
+
+    /// CSV takes the column names from the fields of the first row.
+    pub fn write(&mut self, row: &impl Serialize) -> io::Result<()> {
+        match self {
+            Self::Csv(writer) => writer.serialize(row)?,
+            Self::JsonLines(writer) => {
+                serde_json::to_writer(&mut *writer, row)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the writer and returns it.
+    pub fn finish(self) -> io::Result<W> {
+        let mut writer = match self {
+            Self::Csv(writer) => writer.into_inner().map_err(|err| err.into_error())?,
+            Self::JsonLines(writer) => writer,
+        };
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+#[derive(Serialize)]
+struct GenerationRow<'a> {
+    generation: usize,
+    best_candidate: usize,
+    best_fitness: usize,
+    mean_fitness: f64,
+    worst_fitness: usize,
+    solved: bool,
+    diversity: f64,
+    best_genes: &'a str,
+    elapsed_seconds: f64,
+}
+
+#[derive(Serialize)]
+struct CandidateRow<'a> {
+    generation: usize,
+    candidate: usize,
+    fitness: usize,
+    genes: &'a str,
+}
+
+/// Streams a row of `GenerationStats`, diversity, best genes and elapsed time for every
+/// recorded generation, fed from `Population::run_with`. With `log_population` the genes and
+/// fitness of every candidate are written to a second writer every `interval` generations.
+///
+/// Both writers start with a header holding `config`, which should describe everything the
+/// run depends on, including its seed and limits, so that it can be repeated.
+pub struct GenerationLog<W: Write> {
+    format: LogFormat,
+    /// Serialized once, as every writer starts with it.
+    config: String,
+    start: Instant,
+    writer: RowWriter<W>,
+    population_log: Option<(RowWriter<W>, usize)>,
+}
+
+impl<W: Write> GenerationLog<W> {
+    pub fn new(writer: W, format: LogFormat, config: &impl Serialize) -> io::Result<Self> {
+        let config = serde_json::to_string(config)?;
+        Ok(Self {
+            writer: RowWriter::with_header(writer, format, &config)?,
+            format,
+            config,
+            start: Instant::now(),
+            population_log: None,
+        })
+    }
+
+    /// Also writes every candidate of every `interval`th generation to `writer`, one row each.
+    pub fn log_population(mut self, writer: W, interval: usize) -> io::Result<Self> {
+        let writer = RowWriter::with_header(writer, self.format, &self.config)?;
+        self.population_log = Some((writer, interval.max(1)));
+        Ok(self)
+    }
+
+    pub fn record(&mut self, population: &Population, stats: &GenerationStats) -> io::Result<()> {
+        self.writer.write(&GenerationRow {
+            generation: stats.generation,
+            best_candidate: stats.best_candidate,
+            best_fitness: stats.best_fitness,
+            mean_fitness: stats.mean_fitness,
+            worst_fitness: stats.worst_fitness,
+            solved: stats.solved,
+            diversity: population.diversity(),
+            best_genes: &population.population[stats.best_candidate].to_string(),
+            elapsed_seconds: self.start.elapsed().as_secs_f64(),
+        })?;
+
+        if let Some((writer, interval)) = self.population_log.as_mut() {
+            if stats.generation.is_multiple_of(*interval) {
+                for (idx, candidate) in population.population.iter().enumerate() {
+                    writer.write(&CandidateRow {
+                        generation: stats.generation,
+                        candidate: idx,
+                        fitness: candidate.fitness,
+                        genes: &candidate.to_string(),
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the writers, returning them with the population writer second.
+    pub fn finish(self) -> io::Result<(W, Option<W>)> {
+        let population_writer = self
+            .population_log
+            .map(|(writer, _)| writer.finish())
+            .transpose()?;
+        Ok((self.writer.finish()?, population_writer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{biased_scale::BiasedScale, run::RunLimits};
+    use serde::Deserialize;
+    use std::ops::ControlFlow;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        seed: u64,
+        max_generations: Option<usize>,
+    }
+
+    #[derive(Deserialize)]
+    struct Generation {
+        generation: usize,
+        best_fitness: usize,
+        solved: bool,
+        best_genes: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Candidate {
+        generation: usize,
+        candidate: usize,
+        fitness: usize,
+    }
+
+    const CONFIG: Config = Config {
+        seed: 3,
+        max_generations: Some(12),
+    };
+
+    /// Runs a small population through a log, returning the rows it was given and what was
+    /// written.
+    fn logged_run(format: LogFormat) -> (Vec<GenerationStats>, Vec<u8>, Vec<u8>) {
+        let mut population = Population::with_seed(
+            "Hello World".to_owned(),
+            2,
+            30,
+            BiasedScale::Order(2.0),
+            CONFIG.seed,
+        );
+        let limits = RunLimits {
+            max_generations: CONFIG.max_generations,
+            max_duration: None,
+        };
+        let mut log = GenerationLog::new(Vec::new(), format, &CONFIG)
+            .and_then(|log| log.log_population(Vec::new(), 5))
+            .unwrap();
+        let mut recorded = Vec::new();
+        population.run_with(&limits, |population, stats| {
+            log.record(population, stats).unwrap();
+            recorded.push(*stats);
+            ControlFlow::Continue(())
+        });
+        let (rows, candidates) = log.finish().unwrap();
+        (recorded, rows, candidates.unwrap())
+    }
+
+    fn check(recorded: &[GenerationStats], rows: Vec<Generation>, candidates: Vec<Candidate>) {
+        assert_eq!(rows.len(), recorded.len());
+        for (row, stats) in rows.iter().zip(recorded) {
+            assert_eq!(row.generation, stats.generation);
+            assert_eq!(row.best_fitness, stats.best_fitness);
+            assert_eq!(row.solved, stats.solved);
+            assert_eq!(row.best_genes.len(), "Hello World".len());
+        }
+        let logged_generations = recorded
+            .iter()
+            .filter(|stats| stats.generation.is_multiple_of(5))
+            .count();
+        assert_eq!(candidates.len(), logged_generations * 30);
+        assert!(candidates.iter().all(|row| row.generation.is_multiple_of(5)
+            && row.candidate < 30
+            && row.fitness <= 11));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        fn parse<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Vec<T> {
+            let (header, rows) = std::str::from_utf8(bytes)
+                .unwrap()
+                .split_once('\n')
+                .unwrap();
+            let config: Config =
+                serde_json::from_str(header.strip_prefix("# config: ").unwrap()).unwrap();
+            assert_eq!(config, CONFIG);
+            csv::Reader::from_reader(rows.as_bytes())
+                .deserialize()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        }
+
+        let (recorded, rows, candidates) = logged_run(LogFormat::Csv);
+        check(&recorded, parse(&rows), parse(&candidates));
+    }
+
+    #[test]
+    fn json_lines_round_trip() {
+        #[derive(Deserialize)]
+        struct Header {
+            config: Config,
+        }
+
+        fn parse<T: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Vec<T> {
+            let mut lines = std::str::from_utf8(bytes).unwrap().lines();
+            let header: Header = serde_json::from_str(lines.next().unwrap()).unwrap();
+            assert_eq!(header.config, CONFIG);
+            lines
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+
+        let (recorded, rows, candidates) = logged_run(LogFormat::JsonLines);
+        check(&recorded, parse(&rows), parse(&candidates));
+    }
+}
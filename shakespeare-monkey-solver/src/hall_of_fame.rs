@@ -0,0 +1,160 @@
+use super::dna::Dna;
+use std::collections::HashSet;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HallOfFameEntry {
+    pub genes: Vec<u8>,
+    pub fitness: usize,
+    /// What the archive ranks by: `fitness`, or the score of the population's fitness
+    /// function if it has one.
+    pub score: f64,
+    pub generation: usize,
+}
+
+/// Bounded archive of the highest scoring distinct genomes seen so far, best first.
+/// Among equally scored genomes the earliest discovered ones are kept.
+#[derive(Clone, Debug)]
+pub struct HallOfFame {
+    capacity: usize,
+    entries: Vec<HallOfFameEntry>,
+    /// Genes of every entry.
+    archived: HashSet<Vec<u8>>,
+}
+
+impl HallOfFame {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+            archived: HashSet::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn entries(&self) -> &[HallOfFameEntry] {
+        &self.entries
+    }
+
+    pub fn best(&self) -> Option<&HallOfFameEntry> {
+        self.entries.first()
+    }
+
+    /// Returns whether `candidate`, ranked by `score`, made it into the archive.
+    pub fn offer(&mut self, candidate: &Dna, score: f64, generation: usize) -> bool {
+        let is_full = self.entries.len() >= self.capacity;
+        if is_full
+            && self
+                .entries
+                .last()
+                .is_none_or(|worst| score.total_cmp(&worst.score).is_le())
+        {
+            return false;
+        }
+        if self.archived.contains(&candidate.genes) {
+            return false;
+        }
+
+        let position = self
+            .entries
+            .partition_point(|entry| entry.score.total_cmp(&score).is_ge());
+        self.entries.insert(
+            position,
+            HallOfFameEntry {
+                genes: candidate.genes.clone(),
+                fitness: candidate.fitness,
+                score,
+                generation,
+            },
+        );
+        self.archived.insert(candidate.genes.clone());
+        if self.entries.len() > self.capacity {
+            if let Some(evicted) = self.entries.pop() {
+                self.archived.remove(&evicted.genes);
+            }
+        }
+
+        true
+    }
+
+    /// Offers every candidate of `population`, ranked by `score`.
+    pub fn update(&mut self, population: &[Dna], score: impl Fn(&Dna) -> f64, generation: usize) {
+        for candidate in population {
+            self.offer(candidate, score(candidate), generation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(genes: &[u8], fitness: usize) -> Dna {
+        Dna {
+            fitness,
+            ..Dna::from_genes(genes.to_vec())
+        }
+    }
+
+    fn genes(hall_of_fame: &HallOfFame) -> Vec<&[u8]> {
+        hall_of_fame
+            .entries()
+            .iter()
+            .map(|entry| entry.genes.as_slice())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_best_up_to_its_capacity() {
+        let mut hall_of_fame = HallOfFame::new(3);
+        for (genes, fitness) in [(0, 1), (1, 4), (2, 2), (3, 3), (4, 0)] {
+            hall_of_fame.offer(&candidate(&[genes], fitness), fitness as f64, 0);
+        }
+        assert_eq!(genes(&hall_of_fame), [[1], [3], [2]]);
+        assert_eq!(hall_of_fame.best().unwrap().fitness, 4);
+
+        // The evicted genomes can come back once they beat the worst entry
+        assert!(!hall_of_fame.offer(&candidate(&[0], 1), 1.0, 1));
+        assert!(hall_of_fame.offer(&candidate(&[0], 1), 5.0, 1));
+        assert_eq!(genes(&hall_of_fame), [[0], [1], [3]]);
+        assert!(hall_of_fame.offer(&candidate(&[2], 2), 3.5, 2));
+        assert_eq!(genes(&hall_of_fame), [[0], [1], [2]]);
+    }
+
+    #[test]
+    fn earlier_genomes_win_ties() {
+        let mut hall_of_fame = HallOfFame::new(2);
+        for (genes, generation) in [(0, 0), (1, 1), (2, 2)] {
+            hall_of_fame.offer(&candidate(&[genes], 1), 1.0, generation);
+        }
+        assert_eq!(genes(&hall_of_fame), [[0], [1]]);
+        assert!(hall_of_fame.offer(&candidate(&[3], 1), 2.0, 3));
+        assert_eq!(genes(&hall_of_fame), [[3], [0]]);
+    }
+
+    #[test]
+    fn archives_every_genome_once() {
+        let mut hall_of_fame = HallOfFame::new(3);
+        assert!(hall_of_fame.offer(&candidate(&[0, 1], 1), 1.0, 0));
+        assert!(!hall_of_fame.offer(&candidate(&[0, 1], 1), 3.0, 1));
+        assert!(hall_of_fame.offer(&candidate(&[1, 0], 1), 2.0, 1));
+        assert_eq!(genes(&hall_of_fame), [[1, 0], [0, 1]]);
+        assert_eq!(hall_of_fame.entries()[1].generation, 0);
+    }
+
+    #[test]
+    fn ranks_by_score_rather_than_fitness() {
+        let mut hall_of_fame = HallOfFame::new(2);
+        let population = [candidate(&[0], 3), candidate(&[1], 1), candidate(&[2], 2)];
+        // Scores that prefer fewer matching loci
+        hall_of_fame.update(
+            &population,
+            |candidate| 1.0 / (1 + candidate.fitness) as f64,
+            0,
+        );
+        assert_eq!(genes(&hall_of_fame), [[1], [2]]);
+        assert_eq!(hall_of_fame.best().unwrap().score, 0.5);
+    }
+}
@@ -1,4 +1,20 @@
 pub mod biased_scale;
+pub mod corpus;
 pub mod dna;
+pub mod event_log;
+pub mod fitness;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+pub mod generation_log;
+pub mod hall_of_fame;
+pub mod lineage;
 pub mod population;
+// `std::time::Instant` panics on wasm32
+#[cfg(not(target_arch = "wasm32"))]
+pub mod run;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod segmented;
+pub mod snapshot;
+pub mod statistics;
+pub mod stats;
+pub mod trajectory;
 pub mod utils;
@@ -0,0 +1,297 @@
+use super::dna::{CrossoverSegment, Dna};
+
+pub type LineageId = usize;
+
+#[derive(Clone, Debug)]
+pub struct LineageRecord {
+    pub id: LineageId,
+    pub parents: Option<[LineageId; 2]>,
+    pub birth_generation: usize,
+    pub crossover_segment: Option<CrossoverSegment>,
+    pub mutated_loci: Vec<usize>,
+}
+
+/// Identity and parentage of the individuals created while tracking is enabled, with ids
+/// handed out sequentially. Only the current population and its ancestors born within the
+/// last `window` generations are kept, so memory grows with the window and the population
+/// size rather than with the length of the run.
+#[derive(Clone, Debug, Default)]
+pub struct LineageTracker {
+    /// In id order.
+    records: Vec<LineageRecord>,
+    next_id: LineageId,
+    window: usize,
+    /// Number of records from which `end_generation` prunes.
+    prune_at: usize,
+    current_ids: Vec<LineageId>,
+    next_gen_ids: Vec<LineageId>,
+    fixed_since: Vec<Option<usize>>,
+}
+
+impl LineageTracker {
+    pub fn new(population: &[Dna], target_genes: &[u8], generation: usize, window: usize) -> Self {
+        let mut tracker = Self {
+            window,
+            fixed_since: vec![None; target_genes.len()],
+            ..Default::default()
+        };
+
+        for _ in population {
+            let id = tracker.push_record(None, generation, None);
+            tracker.current_ids.push(id);
+        }
+        tracker.observe_fixation(population, target_genes, generation);
+
+        tracker
+    }
+
+    fn push_record(
+        &mut self,
+        parents: Option<[LineageId; 2]>,
+        birth_generation: usize,
+        crossover_segment: Option<CrossoverSegment>,
+    ) -> LineageId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.records.push(LineageRecord {
+            id,
+            parents,
+            birth_generation,
+            crossover_segment,
+            mutated_loci: Vec::new(),
+        });
+        id
+    }
+
+    /// Registers a child of the population members at `parent_a` and `parent_b`,
+    /// born into `generation`.
+    pub fn record_child(
+        &mut self,
+        parent_a: usize,
+        parent_b: usize,
+        crossover_segment: CrossoverSegment,
+        generation: usize,
+    ) -> LineageId {
+        let parents = [self.current_ids[parent_a], self.current_ids[parent_b]];
+        let id = self.push_record(Some(parents), generation, Some(crossover_segment));
+        self.next_gen_ids.push(id);
+        id
+    }
+
+    /// Carries the population member at `idx` over into the next generation unchanged,
+    /// keeping its id.
+    pub fn record_survivor(&mut self, idx: usize) -> LineageId {
+        let id = self.current_ids[idx];
+        self.next_gen_ids.push(id);
+        id
+    }
+
+    /// Attributes a mutation to the child most recently passed to `record_child`.
+    pub fn record_mutation(&mut self, locus: usize) {
+        if let Some(record) = self.records.last_mut() {
+            record.mutated_loci.push(locus);
+        }
+    }
+
+    /// Makes the recorded children the current population.
+    pub fn end_generation(&mut self, population: &[Dna], target_genes: &[u8], generation: usize) {
+        std::mem::swap(&mut self.current_ids, &mut self.next_gen_ids);
+        self.next_gen_ids.clear();
+        self.observe_fixation(population, target_genes, generation);
+
+        // Pruning again only once the records have doubled keeps its cost linear overall
+        if self.records.len() >= self.prune_at {
+            self.prune(generation);
+            self.prune_at = 2 * self.records.len();
+        }
+    }
+
+    /// Drops the records that are neither in the current population nor among its ancestors
+    /// born within the window before `generation`.
+    fn prune(&mut self, generation: usize) {
+        let cutoff = generation.saturating_sub(self.window);
+        let mut is_kept = vec![false; self.records.len()];
+        let mut pending = Vec::new();
+        for &id in &self.current_ids {
+            if let Some(idx) = self.index_of(id) {
+                is_kept[idx] = true;
+                pending.extend(self.records[idx].parents.iter().flatten());
+            }
+        }
+        while let Some(id) = pending.pop() {
+            if let Some(idx) = self.index_of(id) {
+                let record = &self.records[idx];
+                if !is_kept[idx] && record.birth_generation >= cutoff {
+                    is_kept[idx] = true;
+                    pending.extend(record.parents.iter().flatten());
+                }
+            }
+        }
+
+        let mut is_kept = is_kept.into_iter();
+        self.records.retain(|_| is_kept.next().unwrap());
+    }
+
+    fn index_of(&self, id: LineageId) -> Option<usize> {
+        self.records
+            .binary_search_by_key(&id, |record| record.id)
+            .ok()
+    }
+
+    fn observe_fixation(&mut self, population: &[Dna], target_genes: &[u8], generation: usize) {
+        for ((locus, &token), fixed_since) in target_genes
+            .iter()
+            .enumerate()
+            .zip(self.fixed_since.iter_mut())
+        {
+            let is_fixed = !population.is_empty()
+                && population
+                    .iter()
+                    .all(|candidate| candidate.genes[locus] == token);
+
+            *fixed_since = match (is_fixed, *fixed_since) {
+                (true, Some(since)) => Some(since),
+                (true, None) => Some(generation),
+                (false, _) => None,
+            };
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn id_of(&self, idx: usize) -> Option<LineageId> {
+        self.current_ids.get(idx).copied()
+    }
+
+    pub fn current_ids(&self) -> &[LineageId] {
+        &self.current_ids
+    }
+
+    /// `None` for unknown ids and for individuals that were pruned.
+    pub fn record(&self, id: LineageId) -> Option<&LineageRecord> {
+        Some(&self.records[self.index_of(id)?])
+    }
+
+    /// The records kept, in id order.
+    pub fn records(&self) -> &[LineageRecord] {
+        &self.records
+    }
+
+    /// Every distinct ancestor of `id` still kept, including itself, newest first.
+    pub fn ancestry(&self, id: LineageId) -> Vec<&LineageRecord> {
+        let mut visited = vec![false; self.records.len()];
+        let mut pending = vec![id];
+        let mut ancestry = Vec::new();
+
+        while let Some(id) = pending.pop() {
+            let Some(idx) = self.index_of(id) else {
+                continue;
+            };
+            if !visited[idx] {
+                visited[idx] = true;
+                let record = &self.records[idx];
+                ancestry.push(record);
+                pending.extend(record.parents.iter().flatten());
+            }
+        }
+
+        ancestry.sort_unstable_by_key(|record| std::cmp::Reverse(record.id));
+        ancestry
+    }
+
+    /// Follows the gene at `locus` back through the parent it was inherited from,
+    /// up to the individual that introduced it by mutation or, for genes no mutation
+    /// changed, the founder it was inherited from, whose `parents` are `None` and whose
+    /// `mutated_loci` need not contain `locus`. `None` if that individual was born before
+    /// the window.
+    pub fn locus_origin(&self, id: LineageId, locus: usize) -> Option<&LineageRecord> {
+        let mut record = self.record(id)?;
+
+        loop {
+            if record.mutated_loci.contains(&locus) {
+                return Some(record);
+            }
+
+            match (record.parents, record.crossover_segment) {
+                (Some([parent_a, parent_b]), Some(crossover_segment)) => {
+                    record = self.record(if crossover_segment.contains(locus) {
+                        parent_b
+                    } else {
+                        parent_a
+                    })?;
+                }
+                _ => return Some(record),
+            }
+        }
+    }
+
+    /// Per locus, the generation since which the whole population has carried the
+    /// target gene there, or `None` if the locus is not currently fixed.
+    pub fn fixation_generations(&self) -> &[Option<usize>] {
+        &self.fixed_since
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{biased_scale::BiasedScale, population::Population};
+
+    #[test]
+    fn records_are_bounded_by_the_window() {
+        let mut population =
+            Population::with_seed("Hello World".to_owned(), 4, 40, BiasedScale::Order(2.0), 2);
+        population.track_lineage(50);
+        let mut max_records = 0;
+        for _ in 0..500 {
+            population.compute_biased_fitness();
+            population.update_generation();
+            max_records = max_records.max(population.lineage.as_ref().unwrap().records().len());
+        }
+        // Without pruning every one of the 500 * 40 children would be kept
+        assert!(max_records <= 2 * 51 * 40, "{max_records} records");
+
+        let lineage = population.lineage.as_ref().unwrap();
+        assert!(lineage
+            .records()
+            .windows(2)
+            .all(|pair| pair[0].id < pair[1].id));
+        for &id in lineage.current_ids() {
+            let ancestry = lineage.ancestry(id);
+            assert_eq!(ancestry[0].id, id);
+            for locus in 0..population.num_genes() {
+                if let Some(origin) = lineage.locus_origin(id, locus) {
+                    assert!(origin.mutated_loci.contains(&locus) || origin.parents.is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unmutated_genes_trace_back_to_their_founder() {
+        // Without mutations every gene is inherited from the founding population, which is
+        // still within the window
+        let mut population =
+            Population::with_seed("Hello World".to_owned(), 0, 20, BiasedScale::Order(2.0), 3);
+        let founders = population.population.clone();
+        population.track_lineage(100);
+        for _ in 0..10 {
+            population.compute_biased_fitness();
+            population.update_generation();
+        }
+
+        let lineage = population.lineage.as_ref().unwrap();
+        for (idx, &id) in lineage.current_ids().iter().enumerate() {
+            for locus in 0..population.num_genes() {
+                let origin = lineage.locus_origin(id, locus).unwrap();
+                assert_eq!(origin.parents, None);
+                assert_eq!(origin.birth_generation, 0);
+                assert_eq!(
+                    founders[origin.id].genes[locus],
+                    population.population[idx].genes[locus]
+                );
+            }
+        }
+    }
+}
@@ -1,12 +1,129 @@
-use super::{biased_scale::BiasedScale, dna::Dna};
-use crate::utils::random::WeightedIndices;
+use super::{
+    biased_scale::BiasedScale,
+    dna::{encode_term, Crossover, CrossoverSegment, Dna},
+    event_log::EventLog,
+    fitness::FitnessFunction,
+    hall_of_fame::HallOfFame,
+    lineage::LineageTracker,
+    snapshot::PopulationSnapshot,
+    stats::{self, GenerationStats},
+};
+use crate::utils::{
+    random::Random,
+    sampler::{Sampler, WeightedSampler},
+};
+
+/// How `compute_biased_fitness` obtains each generation's positional fitness.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FitnessEvaluation {
+    /// Compare every gene of every candidate against the target.
+    Full,
+    /// Derive children's fitness in `update_generation` from their parents' match state,
+    /// the crossover point and the mutated loci. Gives the same results as `Full`.
+    #[default]
+    Incremental,
+}
+
+/// How parents are picked for every child.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Selection {
+    /// Proportionally to biased fitness, drawn with `Population::sampler`.
+    #[default]
+    Proportional,
+    /// Fittest of this many uniformly drawn candidates, so `biased_scale` has no effect.
+    /// With a fitness function the candidates' scores are compared instead.
+    Tournament(usize),
+}
+
+/// Which value tournaments and elitism rank candidates by.
+#[derive(Clone, Copy)]
+enum Ranking {
+    Fitness,
+    Score,
+}
+
+impl Ranking {
+    fn is_fitter(self, candidate: &Dna, other: &Dna) -> bool {
+        match self {
+            Ranking::Fitness => candidate.fitness > other.fitness,
+            Ranking::Score => candidate.biased_fitness > other.biased_fitness,
+        }
+    }
+
+    fn score(self, candidate: &Dna) -> f64 {
+        match self {
+            Ranking::Fitness => candidate.fitness as f64,
+            Ranking::Score => candidate.biased_fitness,
+        }
+    }
+}
+
+enum ParentSelector {
+    Proportional(WeightedSampler),
+    Tournament(usize, Ranking),
+}
+
+impl ParentSelector {
+    fn create(
+        selection: Selection,
+        sampler: Sampler,
+        ranking: Ranking,
+        population: &[Dna],
+    ) -> Self {
+        match selection {
+            Selection::Proportional => Self::Proportional(WeightedSampler::create(
+                sampler,
+                population.iter().map(|candidate| candidate.biased_fitness),
+            )),
+            Selection::Tournament(size) => Self::Tournament(size.max(1), ranking),
+        }
+    }
+
+    fn sample(&self, population: &[Dna], rng: &mut Random) -> usize {
+        match *self {
+            Self::Proportional(ref weighted_sampler) => weighted_sampler.sample(rng),
+            Self::Tournament(size, ranking) => (0..size)
+                .map(|_| rng.gen_range_usize(0..population.len()))
+                .reduce(|best, idx| {
+                    if ranking.is_fitter(&population[idx], &population[best]) {
+                        idx
+                    } else {
+                        best
+                    }
+                })
+                .unwrap(),
+        }
+    }
+}
 
 pub struct Population {
     pub(super) next_gen_population: Vec<Dna>,
+    pub(super) target_genes: Vec<u8>,
+    pub(super) rng: Random,
+    pub(super) fitness_is_current: bool,
+    pub(super) scored_generation: Option<usize>,
+    pub(super) scores: Vec<f64>,
     pub population: Vec<Dna>,
     pub target_term: String,
+    pub seed: u64,
     pub mutation_rate: usize,
     pub biased_scale: BiasedScale,
+    pub sampler: Sampler,
+    pub selection: Selection,
+    pub crossover: Crossover,
+    /// Number of fittest candidates copied unchanged into every next generation.
+    pub elitism: usize,
+    pub fitness_evaluation: FitnessEvaluation,
+    /// Loci `0..frozen_loci` are never mutated and crossover points fall after them, so a
+    /// prefix every candidate shares stays fixed while the rest evolves as if on its own.
+    /// The biased scale only weighs matches after it.
+    /// Not part of a snapshot.
+    pub frozen_loci: usize,
+    pub generation: usize,
+    pub event_log: Option<EventLog>,
+    pub lineage: Option<LineageTracker>,
+    pub hall_of_fame: Option<HallOfFame>,
+    pub fitness_function: Option<Box<dyn FitnessFunction>>,
 }
 
 impl Population {
@@ -16,45 +133,398 @@ impl Population {
         population_size: usize,
         biased_scale: BiasedScale,
     ) -> Self {
-        let population = std::iter::repeat_with(|| Dna::crate_random_genes(target_term.len()))
-            .take(population_size)
-            .collect::<Vec<_>>();
+        Self::with_seed(
+            target_term,
+            mutation_rate,
+            population_size,
+            biased_scale,
+            Random::entropy_seed(),
+        )
+    }
+
+    pub fn with_seed(
+        target_term: String,
+        mutation_rate: usize,
+        population_size: usize,
+        biased_scale: BiasedScale,
+        seed: u64,
+    ) -> Self {
+        let mut rng = Random::from_seed(seed);
+        let target_genes = encode_term(&target_term);
+        let population =
+            std::iter::repeat_with(|| Dna::crate_random_genes(target_genes.len(), &mut rng))
+                .take(population_size)
+                .collect::<Vec<_>>();
         Self {
             next_gen_population: population.clone(),
+            target_genes,
+            rng,
+            fitness_is_current: false,
+            scored_generation: None,
+            scores: Vec::new(),
             population,
             target_term,
+            seed,
             mutation_rate,
             biased_scale,
+            sampler: Sampler::default(),
+            selection: Selection::default(),
+            crossover: Crossover::default(),
+            elitism: 0,
+            fitness_evaluation: FitnessEvaluation::default(),
+            frozen_loci: 0,
+            generation: 0,
+            event_log: None,
+            lineage: None,
+            hall_of_fame: None,
+            fitness_function: None,
+        }
+    }
+
+    pub fn from_snapshot(snapshot: PopulationSnapshot) -> Self {
+        Self {
+            next_gen_population: snapshot.population.clone(),
+            target_genes: encode_term(&snapshot.target_term),
+            rng: Random::from_state(snapshot.random_state),
+            fitness_is_current: false,
+            scored_generation: None,
+            scores: Vec::new(),
+            population: snapshot.population,
+            target_term: snapshot.target_term,
+            seed: snapshot.random_state.seed,
+            mutation_rate: snapshot.mutation_rate,
+            biased_scale: snapshot.biased_scale,
+            sampler: snapshot.sampler,
+            selection: snapshot.selection,
+            crossover: snapshot.crossover,
+            elitism: snapshot.elitism,
+            fitness_evaluation: snapshot.fitness_evaluation,
+            frozen_loci: 0,
+            generation: snapshot.generation,
+            event_log: None,
+            lineage: None,
+            hall_of_fame: snapshot.hall_of_fame,
+            fitness_function: None,
+        }
+    }
+
+    pub fn snapshot(&self) -> PopulationSnapshot {
+        PopulationSnapshot {
+            generation: self.generation,
+            target_term: self.target_term.clone(),
+            random_state: self.rng.state(),
+            mutation_rate: self.mutation_rate,
+            biased_scale: self.biased_scale,
+            sampler: self.sampler,
+            selection: self.selection,
+            crossover: self.crossover,
+            elitism: self.elitism,
+            fitness_evaluation: self.fitness_evaluation,
+            population: self.population.clone(),
+            hall_of_fame: self.hall_of_fame.clone(),
+        }
+    }
+
+    /// Starts logging every following generation update, using the current population
+    /// as the replay starting point.
+    pub fn record_events(&mut self) {
+        self.event_log = Some(EventLog::new(&self.population));
+    }
+
+    /// Starts assigning ids and parentage to every individual from the current
+    /// population onwards, keeping ancestry `window` generations back.
+    pub fn track_lineage(&mut self, window: usize) {
+        self.lineage = Some(LineageTracker::new(
+            &self.population,
+            &self.target_genes,
+            self.generation,
+            window,
+        ));
+    }
+
+    /// Starts archiving the best `capacity` distinct genomes, fed on every fitness computation
+    /// and ranked like elites, by matching loci or by the fitness function's scores.
+    pub fn track_hall_of_fame(&mut self, capacity: usize) {
+        self.hall_of_fame = Some(HallOfFame::new(capacity));
+    }
+
+    pub fn target_genes(&self) -> &[u8] {
+        &self.target_genes
+    }
+
+    pub fn num_genes(&self) -> usize {
+        self.target_genes.len()
+    }
+
+    /// Summarises the fitness values left by the last `compute_biased_fitness`.
+    pub fn stats(&self) -> GenerationStats {
+        GenerationStats::compute(&self.population, self.num_genes(), self.generation)
+    }
+
+    /// See `stats::diversity`.
+    pub fn diversity(&self) -> f64 {
+        stats::diversity(&self.population)
+    }
+
+    /// Forces the next `compute_biased_fitness` to rescan every candidate, needed after
+    /// editing `population` or `target_term` directly.
+    pub fn invalidate_fitness(&mut self) {
+        self.target_genes = encode_term(&self.target_term);
+        self.fitness_is_current = false;
+        self.scored_generation = None;
+    }
+
+    /// Uses `fitness_function` to weigh candidates from the next fitness computation on.
+    pub fn set_fitness_function(&mut self, fitness_function: impl FitnessFunction + 'static) {
+        self.fitness_function = Some(Box::new(fitness_function));
+        self.scored_generation = None;
+    }
+
+    fn ranking(&self) -> Ranking {
+        match self.fitness_function {
+            Some(_) => Ranking::Score,
+            None => Ranking::Fitness,
         }
     }
 
+    /// Counts every candidate's matching loci and weighs it with `biased_scale`, or with
+    /// `fitness_function`, which is called at most once per generation.
     pub fn compute_biased_fitness(&mut self) {
+        let frozen_loci = self.frozen_loci.min(self.target_genes.len());
         for candidate in self.population.iter_mut() {
-            let fitness = candidate.compute_fitness(&self.target_term);
-            candidate.biased_fitness = self.biased_scale.scale(fitness as f64);
+            let fitness = if self.fitness_is_current {
+                candidate.fitness
+            } else {
+                candidate.compute_fitness(&self.target_genes)
+            };
+            let frozen_matches = candidate.matches.count_before(frozen_loci);
+            candidate.biased_fitness = self.biased_scale.scale((fitness - frozen_matches) as f64);
+        }
+        self.fitness_is_current = true;
+
+        if let Some(fitness_function) = self.fitness_function.as_mut() {
+            if self.scored_generation != Some(self.generation) {
+                self.scores.clear();
+                fitness_function.score(&self.population, &mut self.scores);
+                self.scored_generation = Some(self.generation);
+            }
+            for (candidate, &score) in self.population.iter_mut().zip(&self.scores) {
+                candidate.biased_fitness = score;
+            }
+        }
+
+        let ranking = self.ranking();
+        if let Some(hall_of_fame) = self.hall_of_fame.as_mut() {
+            hall_of_fame.update(
+                &self.population,
+                |candidate| ranking.score(candidate),
+                self.generation,
+            );
         }
     }
 
+    /// Indices of the `elitism` fittest candidates, fittest first and earlier on ties.
+    fn elites(&self) -> Vec<usize> {
+        let count = self.elitism.min(self.population.len());
+        let mut ranked = (0..self.population.len()).collect::<Vec<_>>();
+        let ranking = self.ranking();
+        let by_rank = |&a: &usize, &b: &usize| {
+            let (candidate_a, candidate_b) = (&self.population[a], &self.population[b]);
+            let order = match ranking {
+                Ranking::Fitness => candidate_b.fitness.cmp(&candidate_a.fitness),
+                Ranking::Score => candidate_b
+                    .biased_fitness
+                    .total_cmp(&candidate_a.biased_fitness),
+            };
+            order.then(a.cmp(&b))
+        };
+
+        if count > 0 && count < ranked.len() {
+            ranked.select_nth_unstable_by(count - 1, by_rank);
+        }
+        ranked.truncate(count);
+        ranked.sort_unstable_by(by_rank);
+        ranked
+    }
+
     pub fn update_generation(&mut self) {
-        let weighted_indices = WeightedIndices::create(
-            self.population
-                .iter()
-                .map(|candidate| candidate.biased_fitness),
+        let parent_selector = ParentSelector::create(
+            self.selection,
+            self.sampler,
+            self.ranking(),
+            &self.population,
         );
+        let elites = self.elites();
+
+        let num_genes = self.target_genes.len();
+        let frozen_loci = self.frozen_loci.min(num_genes);
+        let child_generation = self.generation + 1;
+        let mut events = self.event_log.as_mut().map(EventLog::begin_generation);
+        let mut lineage = self.lineage.as_mut();
+        let is_incremental =
+            self.fitness_evaluation == FitnessEvaluation::Incremental && self.fitness_is_current;
+        let mut mutated_loci = Vec::new();
 
-        for i in 0..self.population.len() {
-            let mut child_candidate = Dna::crossover(
-                self.pool_selection(&weighted_indices),
-                self.pool_selection(&weighted_indices),
+        for (child_idx, child_candidate) in self.next_gen_population.iter_mut().enumerate() {
+            // Elites are recorded as children of themselves that took no genes from `parent_b`
+            if let Some(&elite) = elites.get(child_idx) {
+                let segment = CrossoverSegment::default();
+                let elite_candidate = &self.population[elite];
+
+                Dna::crossover_into(child_candidate, elite_candidate, elite_candidate, segment);
+                if is_incremental {
+                    Dna::inherit_fitness(
+                        child_candidate,
+                        elite_candidate,
+                        elite_candidate,
+                        segment,
+                    );
+                }
+                if let Some(events) = events.as_mut() {
+                    events.record_child(elite, elite, segment);
+                }
+                if let Some(lineage) = lineage.as_mut() {
+                    lineage.record_survivor(elite);
+                }
+                continue;
+            }
+
+            let (parent_a, parent_b) = (
+                parent_selector.sample(&self.population, &mut self.rng),
+                parent_selector.sample(&self.population, &mut self.rng),
             );
-            child_candidate.mutate(self.mutation_rate);
-            self.next_gen_population[i] = child_candidate;
+            let crossover_segment = if frozen_loci < num_genes {
+                self.crossover
+                    .gen_segment(num_genes - frozen_loci, &mut self.rng)
+                    .shifted(frozen_loci)
+            } else {
+                CrossoverSegment::default()
+            };
+
+            Dna::crossover_into(
+                child_candidate,
+                &self.population[parent_a],
+                &self.population[parent_b],
+                crossover_segment,
+            );
+            if is_incremental {
+                Dna::inherit_fitness(
+                    child_candidate,
+                    &self.population[parent_a],
+                    &self.population[parent_b],
+                    crossover_segment,
+                );
+            }
+            if let Some(events) = events.as_mut() {
+                events.record_child(parent_a, parent_b, crossover_segment);
+            }
+            if let Some(lineage) = lineage.as_mut() {
+                lineage.record_child(parent_a, parent_b, crossover_segment, child_generation);
+            }
+
+            mutated_loci.clear();
+            child_candidate.mutate_from(
+                frozen_loci,
+                self.mutation_rate,
+                &mut self.rng,
+                |locus, gene| {
+                    mutated_loci.push(locus);
+                    if let Some(events) = events.as_mut() {
+                        events.record_mutation(locus, gene);
+                    }
+                    if let Some(lineage) = lineage.as_mut() {
+                        lineage.record_mutation(locus);
+                    }
+                },
+            );
+            if is_incremental {
+                for &locus in &mutated_loci {
+                    child_candidate.refresh_locus(locus, &self.target_genes);
+                }
+            }
         }
 
         std::mem::swap(&mut self.population, &mut self.next_gen_population);
+        self.generation = child_generation;
+        self.fitness_is_current = is_incremental;
+
+        if let Some(lineage) = self.lineage.as_mut() {
+            lineage.end_generation(&self.population, &self.target_genes, self.generation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::RunLimits;
+
+    fn population(fitness_evaluation: FitnessEvaluation, crossover: Crossover) -> Population {
+        let target_term = "To be, or not to be, that is the question: ".repeat(4);
+        let mut population = Population::with_seed(target_term, 2, 40, BiasedScale::Order(2.0), 11);
+        population.fitness_evaluation = fitness_evaluation;
+        population.crossover = crossover;
+        population.elitism = 2;
+        population
+    }
+
+    #[test]
+    fn equal_seeds_give_equal_runs() {
+        let limits = RunLimits {
+            max_generations: Some(50),
+            max_duration: None,
+        };
+        let mut a = population(FitnessEvaluation::Incremental, Crossover::TwoPoint);
+        let mut b = population(FitnessEvaluation::Incremental, Crossover::TwoPoint);
+        assert_eq!(a.run(&limits).generations, b.run(&limits).generations);
+        assert!(a
+            .population
+            .iter()
+            .map(|candidate| &candidate.genes)
+            .eq(b.population.iter().map(|candidate| &candidate.genes)));
     }
 
-    fn pool_selection(&self, weighted_indices: &WeightedIndices) -> &Dna {
-        &self.population[weighted_indices.sample()]
+    #[test]
+    fn elitism_preserves_the_best_candidates() {
+        let mut population = population(FitnessEvaluation::Incremental, Crossover::SinglePoint);
+        population.mutation_rate = 30;
+        let mut best_fitness = 0;
+        for _ in 0..200 {
+            population.compute_biased_fitness();
+            let stats = population.stats();
+            assert!(stats.best_fitness >= best_fitness);
+            best_fitness = stats.best_fitness;
+
+            let elites = population
+                .elites()
+                .into_iter()
+                .map(|elite| population.population[elite].genes.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(elites[0], population.population[stats.best_candidate].genes);
+            population.update_generation();
+            for (child, elite) in population.population.iter().zip(&elites) {
+                assert_eq!(&child.genes, elite);
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_evaluation_matches_full_evaluation() {
+        for crossover in [Crossover::SinglePoint, Crossover::TwoPoint] {
+            let mut full = population(FitnessEvaluation::Full, crossover);
+            let mut incremental = population(FitnessEvaluation::Incremental, crossover);
+            for _ in 0..100 {
+                full.compute_biased_fitness();
+                incremental.compute_biased_fitness();
+                assert_eq!(full.stats(), incremental.stats());
+                for (a, b) in full.population.iter().zip(&incremental.population) {
+                    assert_eq!(a.genes, b.genes);
+                    assert_eq!(a.fitness, b.fitness);
+                    assert_eq!(a.biased_fitness, b.biased_fitness);
+                }
+                full.update_generation();
+                incremental.update_generation();
+            }
+        }
     }
 }
@@ -0,0 +1,89 @@
+use super::{population::Population, stats::GenerationStats};
+use std::{
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
+
+/// Caps after which `Population::run` gives up. `max_generations` counts absolute generations.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RunLimits {
+    pub max_generations: Option<usize>,
+    pub max_duration: Option<Duration>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    Solved,
+    GenerationCap,
+    TimeCap,
+    /// The per-generation callback asked to stop.
+    Stopped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RunResult {
+    pub outcome: RunOutcome,
+    pub generations: usize,
+    /// Fitness evaluations during the run, one per candidate and evaluated generation.
+    pub evaluations: usize,
+    pub elapsed: Duration,
+}
+
+impl RunResult {
+    /// Whether the run ended before a solution was found, so `generations` is only a lower
+    /// bound on its time to solution.
+    pub fn is_censored(&self) -> bool {
+        self.outcome != RunOutcome::Solved
+    }
+}
+
+impl Population {
+    pub fn run(&mut self, limits: &RunLimits) -> RunResult {
+        self.run_with(limits, |_, _| ControlFlow::Continue(()))
+    }
+
+    /// Evaluates and advances generations until solved or a limit is hit, calling
+    /// `on_generation` once every evaluated generation, before it is checked against the limits.
+    pub fn run_with(
+        &mut self,
+        limits: &RunLimits,
+        mut on_generation: impl FnMut(&Population, &GenerationStats) -> ControlFlow<()>,
+    ) -> RunResult {
+        let start = Instant::now();
+        let mut evaluations = 0;
+
+        let outcome = loop {
+            self.compute_biased_fitness();
+            evaluations += self.population.len();
+            let stats = self.stats();
+
+            if on_generation(self, &stats).is_break() {
+                break RunOutcome::Stopped;
+            }
+            if stats.solved {
+                break RunOutcome::Solved;
+            }
+            if limits
+                .max_generations
+                .is_some_and(|max_generations| self.generation >= max_generations)
+            {
+                break RunOutcome::GenerationCap;
+            }
+            if limits
+                .max_duration
+                .is_some_and(|max_duration| start.elapsed() >= max_duration)
+            {
+                break RunOutcome::TimeCap;
+            }
+
+            self.update_generation();
+        };
+
+        RunResult {
+            outcome,
+            generations: self.generation,
+            evaluations,
+            elapsed: start.elapsed(),
+        }
+    }
+}
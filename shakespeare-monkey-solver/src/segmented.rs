@@ -0,0 +1,336 @@
+use super::{
+    dna::encode_term,
+    population::Population,
+    run::{RunLimits, RunResult},
+};
+use crate::utils::derive_seed;
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SegmentStrategy {
+    /// Every segment is evolved by its own population, on as many threads as are available.
+    #[default]
+    Parallel,
+    /// Stage `k` evolves segment `k` after the best genes of stage `k - 1`, which every
+    /// candidate starts with and which are frozen, so each stage only searches its own
+    /// segment and the last stage's best candidate is the result.
+    Curriculum,
+}
+
+/// Evolves a long target term in segments of `segment_length` characters, the last one
+/// shorter, and stitches the best candidates of every segment together.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SegmentedSolver {
+    pub segment_length: usize,
+    pub strategy: SegmentStrategy,
+    /// Caps of every segment's run, or of every curriculum stage's.
+    pub limits: RunLimits,
+}
+
+/// One segment's run, or one curriculum stage's.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentResult {
+    pub index: usize,
+    /// The segment, or for a curriculum stage every segment up to and including it.
+    pub target_term: String,
+    pub best_genes: String,
+    pub best_fitness: usize,
+    pub result: RunResult,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentedResult {
+    /// In segment order.
+    pub segments: Vec<SegmentResult>,
+    pub stitched: String,
+    /// Loci of `stitched` matching the whole target term.
+    pub fitness: usize,
+    pub solved: bool,
+    pub elapsed: Duration,
+}
+
+/// A population built for a segment had no candidates to evolve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmptyPopulation {
+    /// The segment, or curriculum stage, it was built for.
+    pub index: usize,
+}
+
+impl fmt::Display for EmptyPopulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "population of segment {} is empty", self.index)
+    }
+}
+
+impl Error for EmptyPopulation {}
+
+impl SegmentedResult {
+    pub fn generations(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| segment.result.generations)
+            .sum()
+    }
+
+    pub fn evaluations(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|segment| segment.result.evaluations)
+            .sum()
+    }
+}
+
+impl SegmentedSolver {
+    /// Splits `target_term` into its segments.
+    pub fn segments(&self, target_term: &str) -> Vec<String> {
+        target_term
+            .chars()
+            .collect::<Vec<_>>()
+            .chunks(self.segment_length.max(1))
+            .map(|segment| segment.iter().collect())
+            .collect()
+    }
+
+    pub fn solve(
+        &self,
+        target_term: &str,
+        seed: u64,
+        build_population: impl Fn(String, u64) -> Population + Sync,
+    ) -> Result<SegmentedResult, EmptyPopulation> {
+        self.solve_with(target_term, seed, build_population, |_| {})
+    }
+
+    /// Segment `k` is evolved by `build_population(term, derive_seed(seed, k))`, which must
+    /// give non-empty populations of the same size for every term. `on_segment` is called as
+    /// each segment or stage finishes, from the thread that ran it.
+    pub fn solve_with(
+        &self,
+        target_term: &str,
+        seed: u64,
+        build_population: impl Fn(String, u64) -> Population + Sync,
+        on_segment: impl Fn(&SegmentResult) + Sync,
+    ) -> Result<SegmentedResult, EmptyPopulation> {
+        let start = Instant::now();
+        let segments = self.segments(target_term);
+        let (results, stitched) = match self.strategy {
+            SegmentStrategy::Parallel => {
+                let results =
+                    self.solve_parallel(&segments, seed, &build_population, &on_segment)?;
+                let stitched = results
+                    .iter()
+                    .map(|segment| segment.best_genes.as_str())
+                    .collect();
+                (results, stitched)
+            }
+            SegmentStrategy::Curriculum => {
+                let results =
+                    self.solve_curriculum(&segments, seed, &build_population, &on_segment)?;
+                let stitched = results
+                    .last()
+                    .map(|stage| stage.best_genes.clone())
+                    .unwrap_or_default();
+                (results, stitched)
+            }
+        };
+
+        let target_genes = encode_term(target_term);
+        let fitness = encode_term(&stitched)
+            .iter()
+            .zip(&target_genes)
+            .filter(|(gene, target)| gene == target)
+            .count();
+        Ok(SegmentedResult {
+            segments: results,
+            stitched,
+            fitness,
+            solved: fitness == target_genes.len(),
+            elapsed: start.elapsed(),
+        })
+    }
+
+    fn solve_parallel(
+        &self,
+        segments: &[String],
+        seed: u64,
+        build_population: &(impl Fn(String, u64) -> Population + Sync),
+        on_segment: &(impl Fn(&SegmentResult) + Sync),
+    ) -> Result<Vec<SegmentResult>, EmptyPopulation> {
+        let num_threads = thread::available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(segments.len());
+        let next_segment = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(segments.len()));
+        let empty = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..num_threads {
+                scope.spawn(|| loop {
+                    let index = next_segment.fetch_add(1, Ordering::Relaxed);
+                    let Some(segment) = segments.get(index) else {
+                        break;
+                    };
+                    let mut population =
+                        build_population(segment.clone(), derive_seed(seed, index as u64));
+                    if population.population.is_empty() {
+                        // Keeps the other threads from starting further segments.
+                        next_segment.store(segments.len(), Ordering::Relaxed);
+                        empty
+                            .lock()
+                            .unwrap()
+                            .get_or_insert(EmptyPopulation { index });
+                        break;
+                    }
+                    let result = population.run(&self.limits);
+                    let segment = Self::segment_result(index, &population, result);
+                    on_segment(&segment);
+                    results.lock().unwrap().push(segment);
+                });
+            }
+        });
+
+        if let Some(empty) = empty.into_inner().unwrap() {
+            return Err(empty);
+        }
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable_by_key(|segment| segment.index);
+        Ok(results)
+    }
+
+    fn solve_curriculum(
+        &self,
+        segments: &[String],
+        seed: u64,
+        build_population: &impl Fn(String, u64) -> Population,
+        on_segment: &impl Fn(&SegmentResult),
+    ) -> Result<Vec<SegmentResult>, EmptyPopulation> {
+        let mut results = Vec::with_capacity(segments.len());
+        // Best genes of the last stage, which every candidate of the next stage starts with
+        let mut prefix = Vec::new();
+        let mut target_term = String::new();
+
+        for (index, segment) in segments.iter().enumerate() {
+            target_term.push_str(segment);
+            let mut population =
+                build_population(target_term.clone(), derive_seed(seed, index as u64));
+            if population.population.is_empty() {
+                return Err(EmptyPopulation { index });
+            }
+            for candidate in population.population.iter_mut() {
+                candidate.genes[..prefix.len()].copy_from_slice(&prefix);
+            }
+            population.frozen_loci = prefix.len();
+            population.invalidate_fitness();
+
+            let result = population.run(&self.limits);
+            let stage = Self::segment_result(index, &population, result);
+            prefix = population.population[population.stats().best_candidate]
+                .genes
+                .clone();
+            on_segment(&stage);
+            results.push(stage);
+        }
+        Ok(results)
+    }
+
+    fn segment_result(index: usize, population: &Population, result: RunResult) -> SegmentResult {
+        let stats = population.stats();
+        SegmentResult {
+            index,
+            target_term: population.target_term.clone(),
+            best_genes: population.population[stats.best_candidate].to_string(),
+            best_fitness: stats.best_fitness,
+            result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biased_scale::BiasedScale;
+
+    fn solver(strategy: SegmentStrategy) -> SegmentedSolver {
+        SegmentedSolver {
+            segment_length: 4,
+            strategy,
+            limits: RunLimits {
+                max_generations: Some(2000),
+                max_duration: None,
+            },
+        }
+    }
+
+    fn build(population_size: usize) -> impl Fn(String, u64) -> Population + Sync {
+        move |target_term, seed| {
+            Population::with_seed(
+                target_term,
+                1,
+                population_size,
+                BiasedScale::Order(2.0),
+                seed,
+            )
+        }
+    }
+
+    #[test]
+    fn stages_cover_growing_prefixes() {
+        let target_term = "Hello World";
+        for strategy in [SegmentStrategy::Parallel, SegmentStrategy::Curriculum] {
+            let result = solver(strategy).solve(target_term, 1, build(40)).unwrap();
+            let terms = result
+                .segments
+                .iter()
+                .map(|segment| segment.target_term.as_str())
+                .collect::<Vec<_>>();
+            match strategy {
+                SegmentStrategy::Parallel => assert_eq!(terms, ["Hell", "o Wo", "rld"]),
+                SegmentStrategy::Curriculum => {
+                    assert_eq!(terms, ["Hell", "Hello Wo", "Hello World"])
+                }
+            }
+            assert_eq!(result.stitched.chars().count(), target_term.chars().count());
+            assert_eq!(result.solved, result.stitched == target_term);
+        }
+    }
+
+    #[test]
+    fn curriculum_stages_keep_the_prefix() {
+        let solver = SegmentedSolver {
+            limits: RunLimits {
+                max_generations: Some(5),
+                max_duration: None,
+            },
+            ..solver(SegmentStrategy::Curriculum)
+        };
+        // A high mutation rate would soon break an unfrozen prefix
+        let build = |target_term, seed| {
+            Population::with_seed(target_term, 30, 20, BiasedScale::Order(2.0), seed)
+        };
+        let result = solver
+            .solve("To be, or not to be, that is the question", 4, build)
+            .unwrap();
+        // Too few generations to solve any segment, so only freezing keeps the prefixes
+        assert!(!result.solved);
+        for stages in result.segments.windows(2) {
+            assert!(stages[1].best_genes.starts_with(&stages[0].best_genes));
+        }
+        assert_eq!(result.stitched, result.segments.last().unwrap().best_genes);
+    }
+
+    #[test]
+    fn empty_populations_are_an_error() {
+        for strategy in [SegmentStrategy::Parallel, SegmentStrategy::Curriculum] {
+            let result = solver(strategy).solve("Hello World", 1, build(0));
+            assert_eq!(result, Err(EmptyPopulation { index: 0 }));
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{
+    biased_scale::BiasedScale,
+    dna::{Crossover, Dna},
+    hall_of_fame::HallOfFame,
+    population::{FitnessEvaluation, Selection},
+};
+use crate::utils::{sampler::Sampler, RandomState};
+
+/// Point-in-time copy of a `Population`, enough to resume it with `Population::from_snapshot`.
+/// Event logs, lineage and the fitness function are not part of a snapshot.
+#[derive(Clone, Debug)]
+pub struct PopulationSnapshot {
+    pub generation: usize,
+    pub target_term: String,
+    pub random_state: RandomState,
+    pub mutation_rate: usize,
+    pub biased_scale: BiasedScale,
+    pub sampler: Sampler,
+    pub selection: Selection,
+    pub crossover: Crossover,
+    pub elitism: usize,
+    pub fitness_evaluation: FitnessEvaluation,
+    pub population: Vec<Dna>,
+    pub hall_of_fame: Option<HallOfFame>,
+}
@@ -0,0 +1,542 @@
+/// Two-sided 95% critical values of Student's t for 1 to 30 degrees of freedom.
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+const Z_CRITICAL_95: f64 = 1.960;
+
+fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    T_CRITICAL_95
+        .get(degrees_of_freedom.wrapping_sub(1))
+        .copied()
+        .unwrap_or(Z_CRITICAL_95)
+}
+
+/// Summary of the generations taken over the trials of one grid point. Censored trials enter
+/// with the generation they were stopped at, so with censoring these are lower bounds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrialSummary {
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: usize,
+    pub max: usize,
+    /// 95% confidence interval of the mean, collapsed onto the mean for a single trial. Its
+    /// lower bound is clamped at zero, as the samples are counts.
+    pub confidence_interval: (f64, f64),
+}
+
+impl TrialSummary {
+    pub fn from_samples(samples: &[usize]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let count = sorted.len();
+        let mean = sorted.iter().sum::<usize>() as f64 / count as f64;
+        let median = match count % 2 {
+            0 => (sorted[count / 2 - 1] + sorted[count / 2]) as f64 / 2.0,
+            _ => sorted[count / 2] as f64,
+        };
+        let std_dev = match count {
+            1 => 0.0,
+            _ => (sorted
+                .iter()
+                .map(|&sample| (sample as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1) as f64)
+                .sqrt(),
+        };
+        let half_width = t_critical_95(count - 1) * std_dev / (count as f64).sqrt();
+
+        Self {
+            mean,
+            median,
+            std_dev,
+            min: sorted[0],
+            max: sorted[count - 1],
+            confidence_interval: ((mean - half_width).max(0.0), mean + half_width),
+        }
+    }
+}
+
+/// Kaplan–Meier estimate of the probability that a run is still unsolved after each
+/// generation, treating runs stopped by a cap as censored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SurvivalCurve {
+    /// Generations at which at least one run was solved, ascending.
+    pub generations: Vec<usize>,
+    /// Estimated fraction of runs still unsolved just after each of `generations`.
+    pub survival: Vec<f64>,
+    /// First generation by which half the runs are estimated to be solved, if reached.
+    pub median: Option<usize>,
+}
+
+impl SurvivalCurve {
+    /// `samples` are `(generations, censored)` pairs, one per run.
+    pub fn kaplan_meier(samples: &[(usize, bool)]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let mut curve = Self::default();
+        let mut at_risk = sorted.len();
+        let mut survival = 1.0;
+
+        // Sorting puts solved runs before runs censored at the same generation, so those
+        // still count as at risk when the solved ones are removed
+        for group in sorted.chunk_by(|a, b| a.0 == b.0) {
+            let solved = group.iter().filter(|&&(_, censored)| !censored).count();
+            if solved > 0 {
+                survival *= 1.0 - solved as f64 / at_risk as f64;
+                curve.generations.push(group[0].0);
+                curve.survival.push(survival);
+                if curve.median.is_none() && survival <= 0.5 {
+                    curve.median = Some(group[0].0);
+                }
+            }
+            at_risk -= group.len();
+        }
+
+        curve
+    }
+}
+
+/// Whether run `a` certainly solved in fewer generations than run `b`, given that a censored
+/// run would only have been solved after the generation it was stopped at.
+fn is_faster((a, a_censored): (usize, bool), (b, b_censored): (usize, bool)) -> bool {
+    !a_censored && (a < b || b_censored && a <= b)
+}
+
+/// Gehan's scores of the pooled `samples`: how many runs were certainly faster minus how many
+/// were certainly slower. Without censoring these are `2 * rank - (n + 1)` with mid-ranks,
+/// so tests built on them reduce to the usual rank tests.
+fn gehan_scores(samples: &[(usize, bool)]) -> Vec<f64> {
+    samples
+        .iter()
+        .map(|&sample| {
+            samples
+                .iter()
+                .map(|&other| is_faster(other, sample) as i64 - is_faster(sample, other) as i64)
+                .sum::<i64>() as f64
+        })
+        .collect()
+}
+
+/// Mann–Whitney U test of two samples of `(generations, censored)` runs, using Gehan's
+/// generalisation for censored runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MannWhitney {
+    /// Pairs in which `a` was certainly faster, plus half the pairs that are tied or cannot
+    /// be ordered because of censoring.
+    pub u: f64,
+    /// Probability that `a` is faster minus the probability that `b` is, from -1 to 1.
+    pub cliffs_delta: f64,
+    /// Normal approximation of the statistic, positive when `a` tends to be faster.
+    pub z: f64,
+    /// Two-sided.
+    pub p_value: f64,
+}
+
+impl MannWhitney {
+    pub fn test(a: &[(usize, bool)], b: &[(usize, bool)]) -> Self {
+        let pairs = (a.len() * b.len()) as f64;
+        let count_faster = |x: &[(usize, bool)], y: &[(usize, bool)]| {
+            x.iter()
+                .flat_map(|&x| y.iter().filter(move |&&y| is_faster(x, y)))
+                .count() as f64
+        };
+        let (a_faster, b_faster) = (count_faster(a, b), count_faster(b, a));
+        if pairs == 0.0 {
+            return Self {
+                p_value: 1.0,
+                ..Self::default()
+            };
+        }
+
+        let pooled = [a, b].concat();
+        let scores = gehan_scores(&pooled);
+        let total = pooled.len() as f64;
+        let variance =
+            pairs * scores.iter().map(|score| score * score).sum::<f64>() / (total * (total - 1.0));
+        let z = if variance > 0.0 {
+            -scores[..a.len()].iter().sum::<f64>() / variance.sqrt()
+        } else {
+            0.0
+        };
+
+        Self {
+            u: a_faster + (pairs - a_faster - b_faster) / 2.0,
+            cliffs_delta: (a_faster - b_faster) / pairs,
+            z,
+            p_value: (2.0 * normal_sf(z.abs())).min(1.0),
+        }
+    }
+}
+
+/// Kruskal–Wallis H test of two or more samples of `(generations, censored)` runs, using
+/// Gehan's scores in place of ranks for censored runs.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct KruskalWallis {
+    pub statistic: f64,
+    pub degrees_of_freedom: usize,
+    pub p_value: f64,
+}
+
+impl KruskalWallis {
+    pub fn test(groups: &[&[(usize, bool)]]) -> Self {
+        let pooled = groups.concat();
+        let scores = gehan_scores(&pooled);
+        let sum_of_squares = scores.iter().map(|score| score * score).sum::<f64>();
+        // Empty groups add nothing to compare
+        let degrees_of_freedom = groups
+            .iter()
+            .filter(|group| !group.is_empty())
+            .count()
+            .saturating_sub(1);
+        if sum_of_squares == 0.0 || degrees_of_freedom == 0 {
+            return Self {
+                statistic: 0.0,
+                degrees_of_freedom,
+                p_value: 1.0,
+            };
+        }
+
+        let mut offset = 0;
+        let between = groups
+            .iter()
+            .filter(|group| !group.is_empty())
+            .map(|group| {
+                let sum = scores[offset..offset + group.len()].iter().sum::<f64>();
+                offset += group.len();
+                sum * sum / group.len() as f64
+            })
+            .sum::<f64>();
+        let statistic = (pooled.len() - 1) as f64 * between / sum_of_squares;
+
+        Self {
+            statistic,
+            degrees_of_freedom,
+            p_value: chi_squared_sf(statistic, degrees_of_freedom),
+        }
+    }
+}
+
+/// Holm–Bonferroni adjustment of `p_values` for multiple comparisons, in the same order.
+pub fn holm(p_values: &[f64]) -> Vec<f64> {
+    let mut order = (0..p_values.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| p_values[a].total_cmp(&p_values[b]));
+
+    let mut adjusted = vec![0.0; p_values.len()];
+    let mut running_max: f64 = 0.0;
+    for (rank, idx) in order.into_iter().enumerate() {
+        running_max = running_max.max(((p_values.len() - rank) as f64 * p_values[idx]).min(1.0));
+        adjusted[idx] = running_max;
+    }
+    adjusted
+}
+
+/// Upper tail of the standard normal distribution.
+fn normal_sf(z: f64) -> f64 {
+    erfc(z / std::f64::consts::SQRT_2) / 2.0
+}
+
+/// Complementary error function with a fractional error below 1.2e-7 (Numerical Recipes'
+/// Chebyshev fit).
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.5 * x.abs());
+    let poly = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, coefficient| acc * t + coefficient);
+    let value = t * (-x * x + poly).exp();
+
+    if x >= 0.0 {
+        value
+    } else {
+        2.0 - value
+    }
+}
+
+/// Upper tail of the chi-squared distribution.
+fn chi_squared_sf(x: f64, degrees_of_freedom: usize) -> f64 {
+    regularized_gamma_q(degrees_of_freedom as f64 / 2.0, x / 2.0)
+}
+
+/// Lanczos approximation (g = 7, n = 9) of `ln Γ(x)` for positive `x`.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 8] = [
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    let x = x - 1.0;
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(0.999_999_999_999_809_9, |acc, (idx, coefficient)| {
+            acc + coefficient / (x + idx as f64 + 1.0)
+        });
+    let t = x + 7.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// `Q(a, x) = Γ(a, x) / Γ(a)`, by its series below `a + 1` and its continued fraction above.
+fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-14;
+    const MAX_ITERATIONS: usize = 500;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+        return (1.0 - sum * prefactor).max(0.0);
+    }
+
+    // Modified Lentz's method
+    let tiny = f64::MIN_POSITIVE / EPSILON;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+    for n in 1..MAX_ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction * prefactor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `(generations, censored)` of every run.
+    type Runs = Vec<(usize, bool)>;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{actual} is not {expected}"
+        );
+    }
+
+    fn solved(generations: &[usize]) -> Runs {
+        generations
+            .iter()
+            .map(|&generation| (generation, false))
+            .collect()
+    }
+
+    /// Weeks in remission of the 6-MP and placebo groups of Freireich et al. (1963), the
+    /// example of Gehan (1965).
+    fn leukemia() -> (Runs, Runs) {
+        let six_mp = [
+            (6, false),
+            (6, false),
+            (6, false),
+            (6, true),
+            (7, false),
+            (9, true),
+            (10, false),
+            (10, true),
+            (11, true),
+            (13, false),
+            (16, false),
+            (17, true),
+            (19, true),
+            (20, true),
+            (22, false),
+            (23, false),
+            (25, true),
+            (32, true),
+            (32, true),
+            (34, true),
+            (35, true),
+        ];
+        let placebo = solved(&[
+            1, 1, 2, 2, 3, 4, 4, 5, 5, 8, 8, 8, 8, 11, 11, 12, 12, 15, 17, 22, 23,
+        ]);
+        (six_mp.to_vec(), placebo)
+    }
+
+    #[test]
+    fn kaplan_meier_keeps_runs_censored_at_a_tie_at_risk() {
+        let curve = SurvivalCurve::kaplan_meier(&[
+            (5, false),
+            (2, true),
+            (1, false),
+            (3, false),
+            (2, false),
+            (4, true),
+        ]);
+        assert_eq!(curve.generations, [1, 2, 3, 5]);
+        for (survival, expected) in
+            curve
+                .survival
+                .iter()
+                .zip([5.0 / 6.0, 4.0 / 6.0, 4.0 / 9.0, 0.0])
+        {
+            assert_close(*survival, expected, 1e-12);
+        }
+        assert_eq!(curve.median, Some(3));
+    }
+
+    #[test]
+    fn mann_whitney_matches_the_rank_test_without_censoring() {
+        let test = MannWhitney::test(&solved(&[1, 2, 3]), &solved(&[4, 5, 6]));
+        assert_eq!(test.u, 9.0);
+        assert_eq!(test.cliffs_delta, 1.0);
+        assert_close(test.z, 1.963_961_012, 1e-9);
+        assert_close(test.p_value, 0.049_534_613, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_matches_gehans_example() {
+        let (six_mp, placebo) = leukemia();
+        let test = MannWhitney::test(&six_mp, &placebo);
+        // Gehan's W = 271 with variance 5644.39 puts 6-MP's remissions ahead
+        assert_close(test.z, -271.0 / 5_644.390_243_9_f64.sqrt(), 1e-9);
+        assert_close(test.p_value, 3.096_1e-4, 1e-3);
+        assert_close(test.cliffs_delta, (64.0 - 335.0) / 441.0, 1e-12);
+    }
+
+    #[test]
+    fn kruskal_wallis_matches_the_rank_test() {
+        let test = KruskalWallis::test(&[
+            &solved(&[1, 2, 3]),
+            &solved(&[4, 5, 6]),
+            &solved(&[7, 8, 9]),
+        ]);
+        assert_close(test.statistic, 7.2, 1e-12);
+        assert_eq!(test.degrees_of_freedom, 2);
+        assert_close(test.p_value, (-3.6_f64).exp(), 1e-9);
+
+        // Tied runs take the mid-rank, and H is corrected for the ties
+        let test = KruskalWallis::test(&[
+            &solved(&[1, 2, 2]),
+            &solved(&[2, 3, 4]),
+            &solved(&[4, 5, 6]),
+        ]);
+        assert_close(test.statistic, 6.330_434_783, 1e-9);
+        assert_close(test.p_value, 0.042_204_966, 1e-7);
+    }
+
+    #[test]
+    fn kruskal_wallis_of_two_groups_is_gehans_test_squared() {
+        let (six_mp, placebo) = leukemia();
+        let test = KruskalWallis::test(&[&six_mp, &placebo]);
+        assert_close(test.statistic, 13.011_325_728, 1e-9);
+        assert_eq!(test.degrees_of_freedom, 1);
+        assert_close(
+            test.p_value,
+            MannWhitney::test(&six_mp, &placebo).p_value,
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn kruskal_wallis_ignores_empty_groups() {
+        let (a, b) = (solved(&[1, 2, 3]), solved(&[4, 5, 6]));
+        assert_eq!(
+            KruskalWallis::test(&[&a, &[], &b]),
+            KruskalWallis::test(&[&a, &b])
+        );
+        assert_eq!(KruskalWallis::test(&[&a, &[]]).degrees_of_freedom, 0);
+    }
+
+    #[test]
+    fn identical_runs_are_not_significant() {
+        let runs = solved(&[7, 7, 7]);
+        let test = MannWhitney::test(&runs, &runs);
+        assert_eq!((test.u, test.z, test.p_value), (4.5, 0.0, 1.0));
+        let test = KruskalWallis::test(&[&runs, &runs, &runs]);
+        assert_eq!((test.statistic, test.p_value), (0.0, 1.0));
+    }
+
+    #[test]
+    fn holm_multiplies_by_the_remaining_tests_and_stays_monotone() {
+        let adjusted = holm(&[0.01, 0.04, 0.03, 0.005]);
+        for (adjusted, expected) in adjusted.iter().zip([0.03, 0.06, 0.06, 0.02]) {
+            assert_close(*adjusted, expected, 1e-12);
+        }
+        assert_eq!(holm(&[0.5, 0.9]), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn erfc_is_within_its_error_bound() {
+        for (x, expected) in [
+            (0.0, 1.0),
+            (0.5, 0.479_500_122_186_953_5),
+            (1.0, 0.157_299_207_050_285_13),
+            (2.0, 0.004_677_734_981_047_265),
+            (3.0, 2.209_049_699_858_543_8e-5),
+            (-1.0, 1.842_700_792_949_715),
+        ] {
+            assert_close(erfc(x), expected, 1.2e-7);
+        }
+    }
+
+    #[test]
+    fn regularized_gamma_matches_closed_forms() {
+        // Q(1, x) = e^-x, Q(3, x) = e^-x (1 + x + x^2 / 2), Q(1/2, x) = erfc(sqrt(x))
+        assert_close(regularized_gamma_q(1.0, 0.5), (-0.5_f64).exp(), 1e-12);
+        assert_close(regularized_gamma_q(3.0, 2.0), 5.0 * (-2.0_f64).exp(), 1e-12);
+        assert_close(
+            regularized_gamma_q(3.0, 10.0),
+            61.0 * (-10.0_f64).exp(),
+            1e-12,
+        );
+        assert_close(
+            regularized_gamma_q(0.5, 2.0),
+            0.045_500_263_896_358_4,
+            1e-12,
+        );
+        // The 5% critical values of chi-squared with 1 and 2 degrees of freedom
+        assert_close(chi_squared_sf(3.841_459, 1), 0.05, 1e-6);
+        assert_close(chi_squared_sf(5.991_465, 2), 0.05, 1e-6);
+    }
+}
@@ -0,0 +1,68 @@
+use super::dna::Dna;
+
+/// Summary of one evaluated generation, see `Population::stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best_candidate: usize,
+    pub best_fitness: usize,
+    pub mean_fitness: f64,
+    pub worst_fitness: usize,
+    pub solved: bool,
+}
+
+impl GenerationStats {
+    pub fn compute(population: &[Dna], num_genes: usize, generation: usize) -> Self {
+        let mut stats = Self {
+            generation,
+            worst_fitness: population.first().map_or(0, |candidate| candidate.fitness),
+            ..Default::default()
+        };
+
+        let mut total_fitness = 0;
+        for (idx, candidate) in population.iter().enumerate() {
+            if candidate.fitness > stats.best_fitness {
+                stats.best_candidate = idx;
+                stats.best_fitness = candidate.fitness;
+            }
+            stats.worst_fitness = stats.worst_fitness.min(candidate.fitness);
+            total_fitness += candidate.fitness;
+        }
+
+        if !population.is_empty() {
+            stats.mean_fitness = total_fitness as f64 / population.len() as f64;
+            stats.solved = stats.best_fitness == num_genes;
+        }
+
+        stats
+    }
+}
+
+/// Probability that two candidates drawn at random, with replacement, differ at a locus,
+/// averaged over all loci. 0 for a population of clones.
+pub fn diversity(population: &[Dna]) -> f64 {
+    let Some(num_genes) = population.first().map(|candidate| candidate.genes.len()) else {
+        return 0.0;
+    };
+    if num_genes == 0 {
+        return 0.0;
+    }
+
+    let mut counts = [0u32; 256];
+    let num_candidates = population.len() as f64;
+    let total = (0..num_genes)
+        .map(|locus| {
+            counts.fill(0);
+            for candidate in population {
+                counts[candidate.genes[locus] as usize] += 1;
+            }
+            let same = counts
+                .iter()
+                .map(|&count| (count as f64).powi(2))
+                .sum::<f64>();
+            1.0 - same / (num_candidates * num_candidates)
+        })
+        .sum::<f64>();
+
+    total / num_genes as f64
+}
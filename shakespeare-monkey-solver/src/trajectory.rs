@@ -0,0 +1,77 @@
+use super::{population::Population, stats::GenerationStats};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TrajectoryPoint {
+    pub generation: usize,
+    pub best_fitness: usize,
+    pub mean_fitness: f64,
+    /// See `stats::diversity`.
+    pub diversity: f64,
+}
+
+/// Per-generation record of a run, fed from `Population::run_with`.
+///
+/// With `max_points` only every `stride`th generation is kept, and whenever that would exceed
+/// `max_points` the stride doubles and every other point is dropped, so long runs stay evenly
+/// covered in bounded memory. `finish` adds the final generation on top.
+#[derive(Clone, Debug)]
+pub struct Trajectory {
+    max_points: Option<usize>,
+    stride: usize,
+    points: Vec<TrajectoryPoint>,
+}
+
+impl Trajectory {
+    /// `max_points` is raised to at least 2. Without it every generation is kept.
+    pub fn new(max_points: Option<usize>) -> Self {
+        Self {
+            max_points: max_points.map(|max_points| max_points.max(2)),
+            stride: 1,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn points(&self) -> &[TrajectoryPoint] {
+        &self.points
+    }
+
+    pub fn into_points(self) -> Vec<TrajectoryPoint> {
+        self.points
+    }
+
+    /// Diversity is only computed for the generations that are kept.
+    pub fn record(&mut self, population: &Population, stats: &GenerationStats) {
+        if let Some(max_points) = self.max_points {
+            while stats.generation.is_multiple_of(self.stride) && self.points.len() >= max_points {
+                self.stride *= 2;
+                let stride = self.stride;
+                self.points
+                    .retain(|point| point.generation.is_multiple_of(stride));
+            }
+        }
+        if stats.generation.is_multiple_of(self.stride) {
+            self.points.push(Self::point(population, stats));
+        }
+    }
+
+    /// Records the final generation of a finished run, unless it was kept already.
+    pub fn finish(&mut self, population: &Population) {
+        if self
+            .points
+            .last()
+            .is_none_or(|point| point.generation != population.generation)
+        {
+            self.points
+                .push(Self::point(population, &population.stats()));
+        }
+    }
+
+    fn point(population: &Population, stats: &GenerationStats) -> TrajectoryPoint {
+        TrajectoryPoint {
+            generation: stats.generation,
+            best_fitness: stats.best_fitness,
+            mean_fitness: stats.mean_fitness,
+            diversity: population.diversity(),
+        }
+    }
+}
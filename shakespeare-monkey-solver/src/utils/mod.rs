@@ -1,8 +1,36 @@
-use once_cell::sync::Lazy;
-use random::Random;
-use std::sync::Mutex;
+pub mod sampler;
 
-pub static RNG: Lazy<Mutex<Random>> = Lazy::new(|| Mutex::new(Random::new()));
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Added to every weight so candidates without any fitness can still be picked.
+const WEIGHTED_INDEX_OFFSET: f64 = 0.1;
+
+/// Everything needed to rebuild a `Random` at the exact point of its stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RandomState {
+    pub seed: u64,
+    pub position: u128,
+}
+
+/// Mixes `stream` into `seed` (SplitMix64 finaliser), giving well separated seeds for
+/// independent runs that all derive from one base seed.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Seed number `n` taken from `RANDOM_SEED` in this process is `derive_seed(RANDOM_SEED, n)`,
+/// so populations created one after another differ but repeat from one process to the next.
+fn next_fixed_seed(seed: &str) -> u64 {
+    static STREAM: AtomicU64 = AtomicU64::new(0);
+    derive_seed(
+        seed.parse()
+            .expect("RANDOM_SEED should be an unsigned integer"),
+        STREAM.fetch_add(1, Ordering::Relaxed),
+    )
+}
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod random {
@@ -10,29 +38,65 @@ pub mod random {
     use rand_chacha::ChaCha12Rng;
     use std::ops::Range;
 
-    use crate::utils::RNG;
+    use super::{next_fixed_seed, RandomState, WEIGHTED_INDEX_OFFSET};
 
-    pub struct Random(ChaCha12Rng);
+    pub struct Random {
+        seed: u64,
+        rng: ChaCha12Rng,
+    }
+
+    impl Default for Random {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
 
     impl Random {
         pub fn new() -> Self {
-            Random(match option_env!("RANDOM_SEED") {
-                Some(seed) => ChaCha12Rng::seed_from_u64(seed.parse().unwrap()),
-                None => ChaCha12Rng::from_rng(rand::thread_rng()).unwrap(),
-            })
+            Self::from_seed(Self::entropy_seed())
+        }
+
+        /// Random, unless the crate was built with `RANDOM_SEED`, see `next_fixed_seed`.
+        pub fn entropy_seed() -> u64 {
+            match option_env!("RANDOM_SEED") {
+                Some(seed) => next_fixed_seed(seed),
+                None => rand::random(),
+            }
+        }
+
+        pub fn from_seed(seed: u64) -> Self {
+            Self {
+                seed,
+                rng: ChaCha12Rng::seed_from_u64(seed),
+            }
+        }
+
+        pub fn from_state(state: RandomState) -> Self {
+            let mut random = Self::from_seed(state.seed);
+            random.rng.set_word_pos(state.position);
+            random
+        }
+
+        pub fn seed(&self) -> u64 {
+            self.seed
+        }
+
+        pub fn state(&self) -> RandomState {
+            RandomState {
+                seed: self.seed,
+                position: self.rng.get_word_pos(),
+            }
         }
 
         pub fn gen_range_usize(&mut self, range: Range<usize>) -> usize {
-            self.0.gen_range(range)
+            self.rng.gen_range(range)
         }
 
         pub fn gen_range_f64(&mut self, range: Range<f64>) -> f64 {
-            self.0.gen_range(range)
+            self.rng.gen_range(range)
         }
     }
 
-    const WEIGHTED_INDEX_OFFSET: f64 = 0.1;
-
     pub struct WeightedIndices(WeightedIndex<f64>);
 
     impl WeightedIndices {
@@ -43,10 +107,10 @@ pub mod random {
             )
         }
 
-        pub fn sample(&self) -> usize {
+        pub fn sample(&self, rng: &mut Random) -> usize {
             use rand::prelude::Distribution;
 
-            self.0.sample(&mut RNG.lock().unwrap().0)
+            self.0.sample(&mut rng.rng)
         }
     }
 }
@@ -56,26 +120,87 @@ pub mod random {
     use std::ops::Range;
     use wasm_bindgen::prelude::wasm_bindgen;
 
+    use super::{next_fixed_seed, RandomState, WEIGHTED_INDEX_OFFSET};
+
     #[wasm_bindgen]
     extern "C" {
         #[wasm_bindgen(js_namespace = Math, js_name = random)]
         fn gen_random() -> f64;
     }
 
-    pub struct Random;
+    const SPLITMIX_GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Seedable SplitMix64 stream, `Math.random` is only used to pick a seed.
+    pub struct Random {
+        seed: u64,
+        position: u64,
+    }
+
+    impl Default for Random {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
 
     impl Random {
         pub fn new() -> Self {
-            Self
+            Self::from_seed(Self::entropy_seed())
+        }
+
+        /// Random, unless the crate was built with `RANDOM_SEED`, see `next_fixed_seed`.
+        pub fn entropy_seed() -> u64 {
+            match option_env!("RANDOM_SEED") {
+                Some(seed) => next_fixed_seed(seed),
+                None => {
+                    let (high, low) = (gen_random(), gen_random());
+                    ((high * u32::MAX as f64) as u64) << 32 | (low * u32::MAX as f64) as u64
+                }
+            }
+        }
+
+        pub fn from_seed(seed: u64) -> Self {
+            Self { seed, position: 0 }
+        }
+
+        pub fn from_state(state: RandomState) -> Self {
+            Self {
+                seed: state.seed,
+                position: state.position as u64,
+            }
+        }
+
+        pub fn seed(&self) -> u64 {
+            self.seed
+        }
+
+        pub fn state(&self) -> RandomState {
+            RandomState {
+                seed: self.seed,
+                position: self.position as u128,
+            }
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.position += 1;
+            let mut z = self
+                .seed
+                .wrapping_add(self.position.wrapping_mul(SPLITMIX_GAMMA));
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        fn gen_unit(&mut self) -> f64 {
+            (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
         }
 
         pub fn gen_range_usize(&mut self, range: Range<usize>) -> usize {
             let (start, end) = (range.start as f64, range.end as f64);
-            (start + gen_random() * (end - start)).floor() as usize
+            (start + self.gen_unit() * (end - start)).floor() as usize
         }
 
         pub fn gen_range_f64(&mut self, range: Range<f64>) -> f64 {
-            range.start + gen_random() * (range.end - range.start)
+            range.start + self.gen_unit() * (range.end - range.start)
         }
     }
 
@@ -87,7 +212,7 @@ pub mod random {
             let mut weighted_indices = Self(Vec::new());
 
             for elm in iter {
-                cumulative += elm;
+                cumulative += elm + WEIGHTED_INDEX_OFFSET;
                 weighted_indices.0.push(cumulative);
             }
 
@@ -116,3 +241,33 @@ pub mod random {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_seed, random::Random};
+    use std::collections::HashSet;
+
+    #[test]
+    fn derived_seeds_are_deterministic_and_distinct() {
+        assert_eq!(derive_seed(42, 7), derive_seed(42, 7));
+        let seeds = (0..1000)
+            .flat_map(|seed| (0..100).map(move |stream| derive_seed(seed, stream)))
+            .collect::<HashSet<_>>();
+        assert_eq!(seeds.len(), 1000 * 100);
+    }
+
+    #[test]
+    fn random_resumes_from_its_state() {
+        let mut random = Random::from_seed(3);
+        for _ in 0..17 {
+            random.gen_range_usize(0..100);
+        }
+        let mut resumed = Random::from_state(random.state());
+        for _ in 0..100 {
+            assert_eq!(
+                random.gen_range_usize(0..1000),
+                resumed.gen_range_usize(0..1000)
+            );
+        }
+    }
+}